@@ -0,0 +1,54 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Cycle-counter benchmarks, reported in cycles per byte (cpb), on x86 and x86_64.
+//!
+//! `cargo bench --bench cycles [-- <filter>]`. The counter is the time-stamp counter, which
+//! ticks at the nominal frequency: pin the core clock (disable turbo and frequency scaling)
+//! for figures that compare across machines. On other architectures this target does
+//! nothing; use `--bench throughput`.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod suite;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    use std::time::Duration;
+
+    use criterion::{criterion_group, Criterion, Throughput};
+    use criterion_cycles_per_byte::CyclesPerByte;
+
+    fn cycles(c: &mut Criterion<CyclesPerByte>) {
+        super::suite::benches(c, Throughput::Bytes);
+    }
+
+    criterion_group!(
+        name = benches;
+        config = Criterion::default()
+            .with_measurement(CyclesPerByte)
+            .warm_up_time(Duration::from_millis(500))
+            .measurement_time(Duration::from_secs(2));
+        targets = cycles
+    );
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+criterion::criterion_main!(x86::benches);
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn main() {
+    eprintln!("cycles/byte benchmarks need an x86 time-stamp counter; use --bench throughput");
+}
@@ -0,0 +1,180 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Benchmark suite shared by the `throughput` (GB/s) and `cycles` (cycles/byte) targets.
+//!
+//! Every group runs over [`SIZES`], 16 B to 16 MiB. Benchmark ids are
+//! `<cipher>/<backend>/<size>`:
+//!
+//! - `asm`: [`FastChaCha20::apply_keystream`], in place (skipped when the target has no
+//!   assembly);
+//! - `asm-b2b`: [`FastChaCha20::apply_keystream_b2b`], out of place;
+//! - `asm-parallel`: `apply_keystream_parallel` (`rayon` feature);
+//! - `fallback`: [`FastChaCha20::apply_keystream_pure`], the scalar Rust block function;
+//! - `simd4`: [`keystream4`], the four-lane Rust backend behind `batch` on targets without
+//!   assembly, over consecutive blocks;
+//! - `seal` / `open`: the ChaCha20-Poly1305 and XChaCha20-Poly1305 AEADs.
+//!
+//! The Rust backends run with 20, 12 and 8 rounds (`chacha20`, `chacha12`, `chacha8`); the
+//! assembly only implements 20.
+
+use std::hint::black_box;
+
+use criterion::measurement::Measurement;
+use criterion::{BatchSize, BenchmarkGroup, BenchmarkId, Criterion, Throughput};
+use fast_chacha::aead::{ChaCha20Poly1305, XChaCha20Poly1305};
+use fast_chacha::fallback_chacha20::keystream4;
+use fast_chacha::FastChaCha20;
+
+/// Buffer sizes, in bytes: powers of four from 16 B to 16 MiB.
+pub const SIZES: [usize; 11] =
+    [16, 64, 256, 1 << 10, 4 << 10, 16 << 10, 64 << 10, 256 << 10, 1 << 20, 4 << 20, 16 << 20];
+
+/// Cipher names and double-round counts of the Rust backends.
+const ROUNDS: [(&str, usize); 3] = [("chacha20", 10), ("chacha12", 6), ("chacha8", 4)];
+
+const KEY: [u8; 32] = [0x42; 32];
+const NONCE: [u8; 12] = [0x24; 12];
+
+/// Human-readable size, used as the benchmark parameter.
+fn label(size: usize) -> String {
+    match size {
+        s if s >= 1 << 20 => format!("{}MiB", s >> 20),
+        s if s >= 1 << 10 => format!("{}KiB", s >> 10),
+        s => format!("{s}B"),
+    }
+}
+
+/// Runs `routine` on a buffer of every size in [`SIZES`].
+fn over_sizes<M: Measurement, R>(
+    group: &mut BenchmarkGroup<'_, M>,
+    throughput: fn(u64) -> Throughput,
+    mut routine: impl FnMut(&mut [u8]) -> R,
+) {
+    for size in SIZES {
+        let mut buf = vec![0x5au8; size];
+        group.throughput(throughput(size as u64));
+        group.bench_function(BenchmarkId::from_parameter(label(size)), |b| {
+            b.iter(|| routine(black_box(&mut buf)))
+        });
+    }
+}
+
+/// XORs `data` with consecutive keystream blocks computed four at a time by [`keystream4`].
+fn simd4_apply(key: &[u32; 8], counter: &mut [u32; 4], data: &mut [u8], double_rounds: usize) {
+    let mut blocks = [[0u8; 64]; 4];
+    for chunk in data.chunks_mut(256) {
+        let counters = core::array::from_fn(|i| {
+            [counter[0].wrapping_add(i as u32), counter[1], counter[2], counter[3]]
+        });
+        keystream4(&mut blocks, key, &counters, double_rounds);
+        for (d, k) in chunk.iter_mut().zip(blocks.as_flattened()) {
+            *d ^= *k;
+        }
+        counter[0] = counter[0].wrapping_add(4);
+    }
+}
+
+/// Registers every benchmark of the suite.
+///
+/// # Arguments
+/// * `c` - Criterion instance, with the measurement to report.
+/// * `throughput` - How to report a buffer of the given size: `BytesDecimal` gives GB/s
+///   with wall time, `Bytes` gives cycles/byte with a cycle counter.
+pub fn benches<M: Measurement>(c: &mut Criterion<M>, throughput: fn(u64) -> Throughput) {
+    if fast_chacha::is_asm_available() {
+        let mut cipher = FastChaCha20::new(&KEY, &NONCE);
+        let mut group = c.benchmark_group("chacha20/asm");
+        over_sizes(&mut group, throughput, |buf| cipher.apply_keystream(buf));
+        group.finish();
+
+        let mut cipher = FastChaCha20::new(&KEY, &NONCE);
+        let mut group = c.benchmark_group("chacha20/asm-b2b");
+        for size in SIZES {
+            let input = vec![0x5au8; size];
+            let mut output = vec![0u8; size];
+            group.throughput(throughput(size as u64));
+            group.bench_function(BenchmarkId::from_parameter(label(size)), |b| {
+                b.iter(|| cipher.apply_keystream_b2b(black_box(&input), black_box(&mut output)))
+            });
+        }
+        group.finish();
+    }
+
+    #[cfg(feature = "rayon")]
+    {
+        let mut cipher = FastChaCha20::new(&KEY, &NONCE);
+        let mut group = c.benchmark_group("chacha20/asm-parallel");
+        over_sizes(&mut group, throughput, |buf| cipher.apply_keystream_parallel(buf));
+        group.finish();
+    }
+
+    for (name, double_rounds) in ROUNDS {
+        let mut cipher = FastChaCha20::new(&KEY, &NONCE);
+        let mut group = c.benchmark_group(format!("{name}/fallback"));
+        over_sizes(&mut group, throughput, |buf| cipher.apply_keystream_pure(buf, double_rounds));
+        group.finish();
+
+        let key = [u32::from_le_bytes([0x42; 4]); 8];
+        let mut counter = [0, 0x24242424, 0x24242424, 0x24242424];
+        let mut group = c.benchmark_group(format!("{name}/simd4"));
+        over_sizes(&mut group, throughput, |buf| {
+            simd4_apply(&key, &mut counter, buf, double_rounds)
+        });
+        group.finish();
+    }
+
+    // A fresh nonce per seal, as a real sender would use.
+    let aead = ChaCha20Poly1305::new(&KEY);
+    let mut sealed = 0u64;
+    let mut group = c.benchmark_group("chacha20poly1305/seal");
+    over_sizes(&mut group, throughput, |buf| {
+        sealed += 1;
+        let mut nonce = NONCE;
+        nonce[..8].copy_from_slice(&sealed.to_le_bytes());
+        aead.encrypt_in_place_detached(&nonce, b"header", buf).unwrap()
+    });
+    group.finish();
+
+    // Every iteration opens a fresh copy of the sealed buffer; the copy is not timed.
+    // Each size is sealed under its own nonce, distinct from the seal group's too.
+    let mut group = c.benchmark_group("chacha20poly1305/open");
+    for size in SIZES {
+        let mut nonce = [0u8; 12];
+        nonce[4..].copy_from_slice(&(size as u64).to_le_bytes());
+        let mut sealed = vec![0x5au8; size];
+        let tag = aead.encrypt_in_place_detached(&nonce, b"header", &mut sealed).unwrap();
+        group.throughput(throughput(size as u64));
+        group.bench_function(BenchmarkId::from_parameter(label(size)), |b| {
+            b.iter_batched_ref(
+                || sealed.clone(),
+                |buf| aead.decrypt_in_place_detached(&nonce, b"header", buf, &tag).unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+
+    let aead = XChaCha20Poly1305::new(&KEY);
+    let mut group = c.benchmark_group("xchacha20poly1305/seal");
+    over_sizes(&mut group, throughput, |buf| {
+        sealed += 1;
+        let mut nonce = [0x24u8; 24];
+        nonce[..8].copy_from_slice(&sealed.to_le_bytes());
+        aead.encrypt_in_place_detached(&nonce, b"header", buf).unwrap()
+    });
+    group.finish();
+}
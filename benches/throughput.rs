@@ -0,0 +1,38 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Wall-clock benchmarks, reported in GB/s (10^9 bytes per second).
+//!
+//! `cargo bench --bench throughput [-- <filter>]`, e.g. `-- chacha20/asm`.
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+mod suite;
+
+fn throughput(c: &mut Criterion) {
+    suite::benches(c, Throughput::BytesDecimal);
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default()
+        .warm_up_time(Duration::from_millis(500))
+        .measurement_time(Duration::from_secs(2));
+    targets = throughput
+);
+criterion_main!(benches);
@@ -54,6 +54,9 @@ fn main() {
         // RISC-V 64
         ("riscv64", "linux") => "asm/chacha-riscv64-lp64d.S",
         */
+        // WebAssembly has no assembly; the Rust fallback uses simd128 when it is enabled
+        ("wasm32", _) => return,
+
         // fallback to pure-Rust
         _ => {
             println!("cargo:warning=fast_chacha: no ASM for {}-{}, using Rust fallback", arch, os);
@@ -66,9 +69,10 @@ fn main() {
     let final_s = {
         let src_path = Path::new(src_s);
         let file_stem = src_path.file_stem().unwrap().to_string_lossy();
-        let dst = out_dir.join(format!("{}.patched.S", file_stem));
+        let extension = src_path.extension().unwrap().to_string_lossy();
+        let dst = out_dir.join(format!("{}.patched.{}", file_stem, extension));
 
-        match os.as_str() {
+        let asm = match os.as_str() {
             "macos" => {
                 if std::env::var_os("MACOSX_DEPLOYMENT_TARGET").is_none() {
                     std::env::set_var("MACOSX_DEPLOYMENT_TARGET", "11.0");
@@ -119,11 +123,17 @@ fn main() {
                              .set    ChaCha20_ctr32_sve,  _ChaCha20_neon\n",
                     );
                 }
-                fs::write(&dst, asm).expect("failed to write patched .S");
-                dst
+                asm
             }
             "linux" => {
                 let mut asm = std::fs::read_to_string(src_path).unwrap();
+                if arch == "x86_64" {
+                    // Bind the capability vector locally, as OpenSSL's ELF output does, so
+                    // the RIP-relative load also links into shared objects (cdylib).
+                    asm.insert_str(0, ".hidden\tOPENSSL_ia32cap_P\n");
+                }
+                // No executable stack for binaries linking the static library.
+                asm.push_str("\n.section .note.GNU-stack,\"\",%progbits\n");
                 if arch == "aarch64" {
                     // нормализуем глобальные имена
                     asm = asm
@@ -148,14 +158,18 @@ fn main() {
                         );
                     }
                 }
-                fs::write(&dst, asm).expect("failed to write patched .S");
-                dst
+                asm
             }
-            _ => PathBuf::from(src_s),
-        }
+            _ => fs::read_to_string(src_path).unwrap(),
+        };
+
+        // 5) Namespace every global symbol, so the objects can be linked next to
+        //    OpenSSL/BoringSSL, which export the same names.
+        fs::write(&dst, prefix_symbols(&asm)).expect("failed to write patched asm");
+        dst
     };
 
-    // 5) Compile the chosen file
+    // 6) Compile the chosen file
     build
         .file(final_s)
         .flag_if_supported("-fPIC")
@@ -169,3 +183,48 @@ fn main() {
     println!("cargo:rustc-link-lib=static=fast_chacha_asm");
     println!("cargo:rerun-if-changed=build.rs");
 }
+
+/// Global symbols shared with OpenSSL: the asm entry points and the capability words the
+/// asm reads. The Rust side refers to them through `link_name` / `export_name`.
+const SYMBOLS: &[&str] = &[
+    "ChaCha20_ctr32",
+    "ChaCha20_ctr32_dflt",
+    "ChaCha20_ctr32_sve",
+    "ChaCha20_neon",
+    "OPENSSL_armcap_P",
+    "OPENSSL_ia32cap_P",
+    "OPENSSL_mips_cap_P",
+    "OPENSSL_ppccap_P",
+    "OPENSSL_riscvcap_P",
+];
+
+/// Prefix prepended to every symbol in [`SYMBOLS`].
+const SYMBOL_PREFIX: &str = "fast_chacha_";
+
+/// Renames every identifier in [`SYMBOLS`] (and its Mach-O `_` form) to
+/// `fast_chacha_<name>` (`_fast_chacha_<name>`), leaving longer identifiers alone.
+fn prefix_symbols(asm: &str) -> String {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '.';
+    let mut out = String::with_capacity(asm.len() + 1024);
+    let mut rest = asm;
+
+    while let Some(start) = rest.find(is_ident) {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(|c: char| !is_ident(c)).unwrap_or(rest.len());
+        let ident = &rest[..end];
+
+        let (underscore, name) = match ident.strip_prefix('_') {
+            Some(name) if SYMBOLS.contains(&name) => ("_", name),
+            _ => ("", ident),
+        };
+        if SYMBOLS.contains(&name) {
+            out.push_str(underscore);
+            out.push_str(SYMBOL_PREFIX);
+        }
+        out.push_str(name);
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
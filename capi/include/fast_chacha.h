@@ -0,0 +1,158 @@
+/*
+ * fast_chacha C API.
+ *
+ * Generated by cbindgen from capi/src/lib.rs; do not edit.
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>, licensed under the Apache License, Version 2.0.
+ */
+
+#ifndef FAST_CHACHA_H
+#define FAST_CHACHA_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * The operation succeeded.
+ */
+#define FAST_CHACHA_OK 0
+
+/**
+ * A required pointer was NULL.
+ */
+#define FAST_CHACHA_ERROR_INVALID_ARGUMENT -1
+
+/**
+ * A buffer is longer than the cipher can process with one nonce.
+ */
+#define FAST_CHACHA_ERROR_INVALID_LENGTH -2
+
+/**
+ * The authentication tag did not verify; the buffer was left unchanged.
+ */
+#define FAST_CHACHA_ERROR_AUTHENTICATION -3
+
+/**
+ * The library panicked, e.g. because a backend failed its self-test.
+ */
+#define FAST_CHACHA_ERROR_INTERNAL -4
+
+/**
+ * Size of a key, in bytes.
+ */
+#define FAST_CHACHA_KEY_BYTES 32
+
+/**
+ * Size of a ChaCha20 / ChaCha20-Poly1305 nonce, in bytes.
+ */
+#define FAST_CHACHA_NONCE_BYTES 12
+
+/**
+ * Size of an XChaCha20-Poly1305 nonce, in bytes.
+ */
+#define FAST_CHACHA_XNONCE_BYTES 24
+
+/**
+ * Size of an authentication tag, in bytes.
+ */
+#define FAST_CHACHA_TAG_BYTES 16
+
+/**
+ * An opaque ChaCha20 stream cipher, created by `fast_chacha_new`.
+ */
+typedef struct fast_chacha fast_chacha;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the library version as a NUL-terminated string, e.g. `"0.3.0"`.
+ */
+const char *fast_chacha_version(void);
+
+/**
+ * Creates a ChaCha20 cipher positioned at the start of the keystream (block counter 0).
+ *
+ * Returns NULL if `key` or `nonce` is NULL, or if the library panicked. The cipher must be
+ * released with `fast_chacha_free`.
+ */
+struct fast_chacha *fast_chacha_new(const uint8_t *key,
+                                    const uint8_t *nonce);
+
+/**
+ * XORs `len` bytes at `data` in place with the next bytes of the keystream.
+ *
+ * Consecutive calls continue the keystream where the previous one stopped, so this both
+ * encrypts and decrypts.
+ */
+int fast_chacha_apply(struct fast_chacha *cipher,
+                      uint8_t *data,
+                      size_t len);
+
+/**
+ * Moves the cipher to keystream byte `position`.
+ */
+int fast_chacha_seek(struct fast_chacha *cipher,
+                     uint64_t position);
+
+/**
+ * Releases a cipher created by `fast_chacha_new`. NULL is ignored.
+ */
+void fast_chacha_free(struct fast_chacha *cipher);
+
+/**
+ * Encrypts `len` bytes at `buf` in place with ChaCha20-Poly1305 (RFC 8439) and writes
+ * the 16-byte tag to `tag`.
+ *
+ * The nonce must never repeat for the same key.
+ */
+int fast_chacha_aead_seal(const uint8_t *key,
+                          const uint8_t *nonce,
+                          const uint8_t *aad,
+                          size_t aad_len,
+                          uint8_t *buf,
+                          size_t len,
+                          uint8_t *tag);
+
+/**
+ * Verifies `tag` and decrypts `len` bytes at `buf` in place with ChaCha20-Poly1305.
+ *
+ * Returns `FAST_CHACHA_ERROR_AUTHENTICATION`, leaving `buf` unchanged, if the tag does
+ * not verify.
+ */
+int fast_chacha_aead_open(const uint8_t *key,
+                          const uint8_t *nonce,
+                          const uint8_t *aad,
+                          size_t aad_len,
+                          uint8_t *buf,
+                          size_t len,
+                          const uint8_t *tag);
+
+/**
+ * Same as `fast_chacha_aead_seal` with XChaCha20-Poly1305 and a 24-byte nonce, which
+ * may be chosen at random.
+ */
+int fast_chacha_xaead_seal(const uint8_t *key,
+                           const uint8_t *nonce,
+                           const uint8_t *aad,
+                           size_t aad_len,
+                           uint8_t *buf,
+                           size_t len,
+                           uint8_t *tag);
+
+/**
+ * Same as `fast_chacha_aead_open` with XChaCha20-Poly1305 and a 24-byte nonce.
+ */
+int fast_chacha_xaead_open(const uint8_t *key,
+                           const uint8_t *nonce,
+                           const uint8_t *aad,
+                           size_t aad_len,
+                           uint8_t *buf,
+                           size_t len,
+                           const uint8_t *tag);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FAST_CHACHA_H */
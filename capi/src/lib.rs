@@ -0,0 +1,271 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! C ABI for `fast_chacha`, built as the shared library `libfast_chacha_capi.so` (`.dylib`,
+//! `.dll`) and the static library `libfast_chacha_capi.a`, with the header
+//! `include/fast_chacha.h`.
+//!
+//! Every symbol is prefixed with `fast_chacha_`. Invalid arguments are reported with
+//! [`FAST_CHACHA_ERROR_INVALID_ARGUMENT`]. A panic inside the library, such as a failed
+//! self-test when another crate in the build enables the `self_test` feature, is caught at
+//! the FFI boundary and reported with [`FAST_CHACHA_ERROR_INTERNAL`] (NULL from
+//! `fast_chacha_new`). Builds with `panic = "abort"` abort instead.
+//! Lengths are in bytes; a pointer may be NULL when its length is 0. Keys, nonces and tags
+//! are fixed-size arrays of `FAST_CHACHA_*_BYTES` bytes.
+
+#![allow(clippy::missing_safety_doc)]
+
+use core::{ptr, slice};
+use std::ffi::{c_char, c_int};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use chacha::aead::{ChaCha20Poly1305, XChaCha20Poly1305};
+use chacha::{Error, FastChaCha20};
+
+/// The operation succeeded.
+pub const FAST_CHACHA_OK: c_int = 0;
+/// A required pointer was NULL.
+pub const FAST_CHACHA_ERROR_INVALID_ARGUMENT: c_int = -1;
+/// A buffer is longer than the cipher can process with one nonce.
+pub const FAST_CHACHA_ERROR_INVALID_LENGTH: c_int = -2;
+/// The authentication tag did not verify; the buffer was left unchanged.
+pub const FAST_CHACHA_ERROR_AUTHENTICATION: c_int = -3;
+/// The library panicked, e.g. because a backend failed its self-test.
+pub const FAST_CHACHA_ERROR_INTERNAL: c_int = -4;
+
+/// Size of a key, in bytes.
+pub const FAST_CHACHA_KEY_BYTES: usize = 32;
+/// Size of a ChaCha20 / ChaCha20-Poly1305 nonce, in bytes.
+pub const FAST_CHACHA_NONCE_BYTES: usize = 12;
+/// Size of an XChaCha20-Poly1305 nonce, in bytes.
+pub const FAST_CHACHA_XNONCE_BYTES: usize = 24;
+/// Size of an authentication tag, in bytes.
+pub const FAST_CHACHA_TAG_BYTES: usize = 16;
+
+/// An opaque ChaCha20 stream cipher, created by `fast_chacha_new`.
+pub struct FastChaCha {
+    cipher: FastChaCha20,
+}
+
+/// Maps a crate error to a return code.
+fn code(e: Error) -> c_int {
+    match e {
+        Error::AuthenticationFailed => FAST_CHACHA_ERROR_AUTHENTICATION,
+        _ => FAST_CHACHA_ERROR_INVALID_LENGTH,
+    }
+}
+
+/// Runs `f`, turning a panic into [`FAST_CHACHA_ERROR_INTERNAL`] so it cannot unwind into C.
+fn guard(f: impl FnOnce() -> c_int) -> c_int {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(FAST_CHACHA_ERROR_INTERNAL)
+}
+
+/// Borrows `len` bytes at `data`; `data` may only be NULL when `len` is 0.
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (data.is_null(), len) {
+        (_, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts(data, len)),
+    }
+}
+
+/// Mutably borrows `len` bytes at `data`; `data` may only be NULL when `len` is 0.
+unsafe fn bytes_mut<'a>(data: *mut u8, len: usize) -> Option<&'a mut [u8]> {
+    match (data.is_null(), len) {
+        (_, 0) => Some(&mut []),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts_mut(data, len)),
+    }
+}
+
+/// Borrows the fixed-size array at `data`, or `None` if it is NULL.
+unsafe fn array<'a, const N: usize>(data: *const u8) -> Option<&'a [u8; N]> {
+    data.cast::<[u8; N]>().as_ref()
+}
+
+/// Mutably borrows the fixed-size array at `data`, or `None` if it is NULL.
+unsafe fn array_mut<'a, const N: usize>(data: *mut u8) -> Option<&'a mut [u8; N]> {
+    data.cast::<[u8; N]>().as_mut()
+}
+
+/// Returns the library version as a NUL-terminated string, e.g. `"0.3.0"`.
+#[no_mangle]
+pub extern "C" fn fast_chacha_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Creates a ChaCha20 cipher positioned at the start of the keystream (block counter 0).
+///
+/// Returns NULL if `key` or `nonce` is NULL, or if the library panicked. The cipher must be
+/// released with `fast_chacha_free`.
+#[no_mangle]
+pub unsafe extern "C" fn fast_chacha_new(key: *const u8, nonce: *const u8) -> *mut FastChaCha {
+    if key.is_null() || nonce.is_null() {
+        return ptr::null_mut();
+    }
+    match catch_unwind(|| FastChaCha20::new(&*key.cast(), &*nonce.cast())) {
+        Ok(cipher) => Box::into_raw(Box::new(FastChaCha { cipher })),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// XORs `len` bytes at `data` in place with the next bytes of the keystream.
+///
+/// Consecutive calls continue the keystream where the previous one stopped, so this both
+/// encrypts and decrypts.
+#[no_mangle]
+pub unsafe extern "C" fn fast_chacha_apply(
+    cipher: *mut FastChaCha,
+    data: *mut u8,
+    len: usize,
+) -> c_int {
+    guard(|| match (cipher.as_mut(), bytes_mut(data, len)) {
+        (Some(cipher), Some(data)) => {
+            cipher.cipher.apply_keystream(data);
+            FAST_CHACHA_OK
+        }
+        _ => FAST_CHACHA_ERROR_INVALID_ARGUMENT,
+    })
+}
+
+/// Moves the cipher to keystream byte `position`.
+#[no_mangle]
+pub unsafe extern "C" fn fast_chacha_seek(cipher: *mut FastChaCha, position: u64) -> c_int {
+    guard(|| match cipher.as_mut() {
+        Some(cipher) => {
+            cipher.cipher.seek(position);
+            FAST_CHACHA_OK
+        }
+        None => FAST_CHACHA_ERROR_INVALID_ARGUMENT,
+    })
+}
+
+/// Releases a cipher created by `fast_chacha_new`. NULL is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn fast_chacha_free(cipher: *mut FastChaCha) {
+    if !cipher.is_null() {
+        drop(Box::from_raw(cipher));
+    }
+}
+
+/// Encrypts `len` bytes at `buf` in place with ChaCha20-Poly1305 (RFC 8439) and writes
+/// the 16-byte tag to `tag`.
+///
+/// The nonce must never repeat for the same key.
+#[no_mangle]
+pub unsafe extern "C" fn fast_chacha_aead_seal(
+    key: *const u8,
+    nonce: *const u8,
+    aad: *const u8,
+    aad_len: usize,
+    buf: *mut u8,
+    len: usize,
+    tag: *mut u8,
+) -> c_int {
+    guard(|| {
+        let (Some(key), Some(nonce), Some(aad), Some(buf), Some(tag)) =
+            (array(key), array(nonce), bytes(aad, aad_len), bytes_mut(buf, len), array_mut(tag))
+        else {
+            return FAST_CHACHA_ERROR_INVALID_ARGUMENT;
+        };
+        match ChaCha20Poly1305::new(key).encrypt_in_place_detached(nonce, aad, buf) {
+            Ok(t) => {
+                *tag = t;
+                FAST_CHACHA_OK
+            }
+            Err(e) => code(e),
+        }
+    })
+}
+
+/// Verifies `tag` and decrypts `len` bytes at `buf` in place with ChaCha20-Poly1305.
+///
+/// Returns `FAST_CHACHA_ERROR_AUTHENTICATION`, leaving `buf` unchanged, if the tag does
+/// not verify.
+#[no_mangle]
+pub unsafe extern "C" fn fast_chacha_aead_open(
+    key: *const u8,
+    nonce: *const u8,
+    aad: *const u8,
+    aad_len: usize,
+    buf: *mut u8,
+    len: usize,
+    tag: *const u8,
+) -> c_int {
+    guard(|| {
+        let (Some(key), Some(nonce), Some(aad), Some(buf), Some(tag)) =
+            (array(key), array(nonce), bytes(aad, aad_len), bytes_mut(buf, len), array(tag))
+        else {
+            return FAST_CHACHA_ERROR_INVALID_ARGUMENT;
+        };
+        match ChaCha20Poly1305::new(key).decrypt_in_place_detached(nonce, aad, buf, tag) {
+            Ok(()) => FAST_CHACHA_OK,
+            Err(e) => code(e),
+        }
+    })
+}
+
+/// Same as `fast_chacha_aead_seal` with XChaCha20-Poly1305 and a 24-byte nonce, which
+/// may be chosen at random.
+#[no_mangle]
+pub unsafe extern "C" fn fast_chacha_xaead_seal(
+    key: *const u8,
+    nonce: *const u8,
+    aad: *const u8,
+    aad_len: usize,
+    buf: *mut u8,
+    len: usize,
+    tag: *mut u8,
+) -> c_int {
+    guard(|| {
+        let (Some(key), Some(nonce), Some(aad), Some(buf), Some(tag)) =
+            (array(key), array(nonce), bytes(aad, aad_len), bytes_mut(buf, len), array_mut(tag))
+        else {
+            return FAST_CHACHA_ERROR_INVALID_ARGUMENT;
+        };
+        match XChaCha20Poly1305::new(key).encrypt_in_place_detached(nonce, aad, buf) {
+            Ok(t) => {
+                *tag = t;
+                FAST_CHACHA_OK
+            }
+            Err(e) => code(e),
+        }
+    })
+}
+
+/// Same as `fast_chacha_aead_open` with XChaCha20-Poly1305 and a 24-byte nonce.
+#[no_mangle]
+pub unsafe extern "C" fn fast_chacha_xaead_open(
+    key: *const u8,
+    nonce: *const u8,
+    aad: *const u8,
+    aad_len: usize,
+    buf: *mut u8,
+    len: usize,
+    tag: *const u8,
+) -> c_int {
+    guard(|| {
+        let (Some(key), Some(nonce), Some(aad), Some(buf), Some(tag)) =
+            (array(key), array(nonce), bytes(aad, aad_len), bytes_mut(buf, len), array(tag))
+        else {
+            return FAST_CHACHA_ERROR_INVALID_ARGUMENT;
+        };
+        match XChaCha20Poly1305::new(key).decrypt_in_place_detached(nonce, aad, buf, tag) {
+            Ok(()) => FAST_CHACHA_OK,
+            Err(e) => code(e),
+        }
+    })
+}
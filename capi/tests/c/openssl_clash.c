@@ -0,0 +1,85 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*
+ * Link test: defines the global symbols libcrypto exports for its ChaCha20 assembly, as
+ * an application linking OpenSSL or BoringSSL would. Linking must not fail with duplicate
+ * symbols, fast_chacha must not call these functions, and it must not write to these
+ * capability words. Built and run by tests/c_api.rs.
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "fast_chacha.h"
+
+static int stub_calls = 0;
+
+void ChaCha20_ctr32(uint8_t *out, const uint8_t *inp, size_t len, const uint32_t key[8],
+                    const uint32_t counter[4]) {
+    (void)out, (void)inp, (void)len, (void)key, (void)counter;
+    stub_calls++;
+}
+
+void ChaCha20_neon(void) { stub_calls++; }
+void ChaCha20_ctr32_sve(void) { stub_calls++; }
+void ChaCha20_ctr32_dflt(void) { stub_calls++; }
+void ChaCha20_ctr32_c(void) { stub_calls++; }
+
+unsigned int OPENSSL_ia32cap_P[5] = {0xdead0001, 0xdead0002, 0xdead0003, 0xdead0004, 0xdead0005};
+unsigned int OPENSSL_armcap_P = 0xdead0006;
+
+int main(void) {
+    /* RFC 8439 §2.8.2: the tag must come from fast_chacha, not from the stubs. */
+    uint8_t key[FAST_CHACHA_KEY_BYTES];
+    static const uint8_t nonce[FAST_CHACHA_NONCE_BYTES] = {7, 0, 0, 0, 0x40, 0x41,
+                                                           0x42, 0x43, 0x44, 0x45, 0x46, 0x47};
+    static const uint8_t aad[12] = {0x50, 0x51, 0x52, 0x53, 0xc0, 0xc1,
+                                    0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7};
+    static const uint8_t expected_tag[FAST_CHACHA_TAG_BYTES] = {
+        0x1a, 0xe1, 0x0b, 0x59, 0x4f, 0x09, 0xe2, 0x6a,
+        0x7e, 0x90, 0x2e, 0xcb, 0xd0, 0x60, 0x06, 0x91};
+    static const char text[] =
+        "Ladies and Gentlemen of the class of '99: If I could offer you only one tip for "
+        "the future, sunscreen would be it.";
+    uint8_t buf[sizeof text - 1], tag[FAST_CHACHA_TAG_BYTES];
+
+    for (int i = 0; i < FAST_CHACHA_KEY_BYTES; i++) key[i] = (uint8_t)(0x80 + i);
+    memcpy(buf, text, sizeof buf);
+
+    if (fast_chacha_aead_seal(key, nonce, aad, sizeof aad, buf, sizeof buf, tag) !=
+            FAST_CHACHA_OK ||
+        memcmp(tag, expected_tag, sizeof tag) != 0) {
+        fprintf(stderr, "wrong tag\n");
+        return 1;
+    }
+    if (stub_calls != 0) {
+        fprintf(stderr, "fast_chacha called an OpenSSL symbol\n");
+        return 1;
+    }
+    for (int i = 0; i < 5; i++) {
+        if (OPENSSL_ia32cap_P[i] != 0xdead0001u + (unsigned)i) {
+            fprintf(stderr, "fast_chacha wrote OPENSSL_ia32cap_P\n");
+            return 1;
+        }
+    }
+    if (OPENSSL_armcap_P != 0xdead0006u) {
+        fprintf(stderr, "fast_chacha wrote OPENSSL_armcap_P\n");
+        return 1;
+    }
+    printf("no symbol clash with OpenSSL\n");
+    return 0;
+}
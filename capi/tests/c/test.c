@@ -0,0 +1,138 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*
+ * C API test: RFC 8439 §2.4.2 and §2.8.2 vectors, split stream calls, seeking,
+ * XChaCha20-Poly1305 round trip and argument checks. Built and run by tests/c_api.rs.
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "fast_chacha.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                           \
+    do {                                                                      \
+        if (!(cond)) {                                                        \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+            failures++;                                                       \
+        }                                                                     \
+    } while (0)
+
+static const char SUNSCREEN[] =
+    "Ladies and Gentlemen of the class of '99: If I could offer you only one tip for "
+    "the future, sunscreen would be it.";
+
+static void test_stream(void) {
+    uint8_t key[FAST_CHACHA_KEY_BYTES];
+    uint8_t nonce[FAST_CHACHA_NONCE_BYTES] = {0, 0, 0, 0, 0, 0, 0, 0x4a, 0, 0, 0, 0};
+    static const uint8_t expected[16] = {0x6e, 0x2e, 0x35, 0x9a, 0x25, 0x68, 0xf9, 0x80,
+                                         0x41, 0xba, 0x07, 0x28, 0xdd, 0x0d, 0x69, 0x81};
+    uint8_t one_shot[sizeof SUNSCREEN - 1], split[sizeof SUNSCREEN - 1];
+    size_t len = sizeof SUNSCREEN - 1;
+    fast_chacha *cipher;
+
+    for (int i = 0; i < FAST_CHACHA_KEY_BYTES; i++) key[i] = (uint8_t)i;
+    memcpy(one_shot, SUNSCREEN, len);
+    memcpy(split, SUNSCREEN, len);
+
+    /* RFC 8439 §2.4.2 starts at block counter 1. */
+    cipher = fast_chacha_new(key, nonce);
+    CHECK(cipher != NULL);
+    CHECK(fast_chacha_seek(cipher, 64) == FAST_CHACHA_OK);
+    CHECK(fast_chacha_apply(cipher, one_shot, len) == FAST_CHACHA_OK);
+    CHECK(memcmp(one_shot, expected, sizeof expected) == 0);
+    fast_chacha_free(cipher);
+
+    cipher = fast_chacha_new(key, nonce);
+    CHECK(fast_chacha_seek(cipher, 64) == FAST_CHACHA_OK);
+    CHECK(fast_chacha_apply(cipher, split, 7) == FAST_CHACHA_OK);
+    CHECK(fast_chacha_apply(cipher, split + 7, 0) == FAST_CHACHA_OK);
+    CHECK(fast_chacha_apply(cipher, split + 7, len - 7) == FAST_CHACHA_OK);
+    CHECK(memcmp(one_shot, split, len) == 0);
+    fast_chacha_free(cipher);
+}
+
+static void test_aead(void) {
+    uint8_t key[FAST_CHACHA_KEY_BYTES];
+    static const uint8_t nonce[FAST_CHACHA_NONCE_BYTES] = {7, 0, 0, 0, 0x40, 0x41,
+                                                           0x42, 0x43, 0x44, 0x45, 0x46, 0x47};
+    static const uint8_t aad[12] = {0x50, 0x51, 0x52, 0x53, 0xc0, 0xc1,
+                                    0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7};
+    static const uint8_t expected_ct[8] = {0xd3, 0x1a, 0x8d, 0x34, 0x64, 0x8e, 0x60, 0xdb};
+    static const uint8_t expected_tag[FAST_CHACHA_TAG_BYTES] = {
+        0x1a, 0xe1, 0x0b, 0x59, 0x4f, 0x09, 0xe2, 0x6a,
+        0x7e, 0x90, 0x2e, 0xcb, 0xd0, 0x60, 0x06, 0x91};
+    uint8_t buf[sizeof SUNSCREEN - 1], tag[FAST_CHACHA_TAG_BYTES];
+    size_t len = sizeof SUNSCREEN - 1;
+
+    for (int i = 0; i < FAST_CHACHA_KEY_BYTES; i++) key[i] = (uint8_t)(0x80 + i);
+    memcpy(buf, SUNSCREEN, len);
+
+    CHECK(fast_chacha_aead_seal(key, nonce, aad, sizeof aad, buf, len, tag) == FAST_CHACHA_OK);
+    CHECK(memcmp(buf, expected_ct, sizeof expected_ct) == 0);
+    CHECK(memcmp(tag, expected_tag, sizeof tag) == 0);
+
+    tag[0] ^= 1;
+    CHECK(fast_chacha_aead_open(key, nonce, aad, sizeof aad, buf, len, tag) ==
+          FAST_CHACHA_ERROR_AUTHENTICATION);
+    CHECK(memcmp(buf, expected_ct, sizeof expected_ct) == 0);
+    tag[0] ^= 1;
+    CHECK(fast_chacha_aead_open(key, nonce, aad, sizeof aad, buf, len, tag) == FAST_CHACHA_OK);
+    CHECK(memcmp(buf, SUNSCREEN, len) == 0);
+}
+
+static void test_xaead(void) {
+    uint8_t key[FAST_CHACHA_KEY_BYTES] = {1};
+    uint8_t nonce[FAST_CHACHA_XNONCE_BYTES] = {2};
+    uint8_t buf[5] = {'h', 'e', 'l', 'l', 'o'}, tag[FAST_CHACHA_TAG_BYTES];
+
+    CHECK(fast_chacha_xaead_seal(key, nonce, NULL, 0, buf, sizeof buf, tag) == FAST_CHACHA_OK);
+    CHECK(memcmp(buf, "hello", 5) != 0);
+    CHECK(fast_chacha_xaead_open(key, nonce, NULL, 0, buf, sizeof buf, tag) == FAST_CHACHA_OK);
+    CHECK(memcmp(buf, "hello", 5) == 0);
+}
+
+static void test_arguments(void) {
+    uint8_t key[FAST_CHACHA_KEY_BYTES] = {0}, nonce[FAST_CHACHA_NONCE_BYTES] = {0};
+    uint8_t tag[FAST_CHACHA_TAG_BYTES];
+
+    CHECK(fast_chacha_new(NULL, nonce) == NULL);
+    CHECK(fast_chacha_apply(NULL, NULL, 0) == FAST_CHACHA_ERROR_INVALID_ARGUMENT);
+    CHECK(fast_chacha_seek(NULL, 0) == FAST_CHACHA_ERROR_INVALID_ARGUMENT);
+    fast_chacha_free(NULL);
+    CHECK(fast_chacha_aead_seal(key, nonce, NULL, 0, NULL, 0, tag) == FAST_CHACHA_OK);
+    CHECK(fast_chacha_aead_seal(key, nonce, NULL, 1, NULL, 0, tag) ==
+          FAST_CHACHA_ERROR_INVALID_ARGUMENT);
+    CHECK(fast_chacha_aead_open(key, nonce, NULL, 0, NULL, 0, NULL) ==
+          FAST_CHACHA_ERROR_INVALID_ARGUMENT);
+    CHECK(strlen(fast_chacha_version()) > 0);
+}
+
+int main(void) {
+    test_stream();
+    test_aead();
+    test_xaead();
+    test_arguments();
+    if (failures) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("all C API checks passed\n");
+    return 0;
+}
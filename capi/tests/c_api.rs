@@ -0,0 +1,89 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # C API tests
+ *
+ * Checks that the committed `include/fast_chacha.h` matches the header cbindgen generates,
+ * then compiles the programs in `tests/c` with the system C compiler (`$CC`, default `cc`),
+ * links them against the static library and runs them: the API test, and a program that
+ * defines the symbols OpenSSL exports for its ChaCha20 assembly.
+ */
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// Directory holding this test and the library built for it (`target/<profile>/deps`).
+fn deps_dir() -> PathBuf {
+    std::env::current_exe().unwrap().parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn committed_header_is_up_to_date() {
+    let generated = std::fs::read_to_string(Path::new(env!("OUT_DIR")).join("fast_chacha.h"));
+    let committed = std::fs::read_to_string(Path::new(MANIFEST_DIR).join("include/fast_chacha.h"));
+    assert_eq!(
+        committed.unwrap(),
+        generated.unwrap(),
+        "include/fast_chacha.h is stale; copy the generated header from OUT_DIR"
+    );
+}
+
+/// Compiles `tests/c/<source>` against the header and the static library, runs it and
+/// returns its stderr if it fails.
+#[cfg(unix)]
+fn build_and_run(source: &str) -> Result<(), String> {
+    let lib = deps_dir().join("libfast_chacha_capi.a");
+    assert!(lib.exists(), "{} not built", lib.display());
+    let exe = std::env::temp_dir().join(format!("{source}_{}", std::process::id()));
+
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".into());
+    let status = Command::new(cc)
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-I"])
+        .arg(Path::new(MANIFEST_DIR).join("include"))
+        .arg(Path::new(MANIFEST_DIR).join("tests/c").join(source))
+        .arg(&lib)
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&exe)
+        .status()
+        .expect("failed to run the C compiler");
+    if !status.success() {
+        return Err(format!("compiling or linking {source} failed"));
+    }
+
+    let output = Command::new(&exe).output().unwrap();
+    std::fs::remove_file(&exe).unwrap();
+    match output.status.success() {
+        true => Ok(()),
+        false => Err(String::from_utf8_lossy(&output.stderr).into_owned()),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn c_program_against_static_library() {
+    build_and_run("test.c").unwrap();
+}
+
+/// A program defining OpenSSL's `ChaCha20_ctr32` and `OPENSSL_*cap_P` links with the
+/// library, and neither is used by it.
+#[cfg(unix)]
+#[test]
+fn no_symbol_clash_with_openssl() {
+    build_and_run("openssl_clash.c").unwrap();
+}
@@ -0,0 +1,86 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! ctgrind check: runs the cipher, MAC, tag comparison and AEAD paths on inputs marked as
+//! secret, so that Memcheck flags any branch or memory access that depends on them.
+//!
+//! ```sh
+//! CARGO_PROFILE_RELEASE_DEBUG=true cargo build --release -p fast_chacha_ct --bin ctgrind
+//! valgrind --error-exitcode=1 --suppressions=ct/ctgrind.supp target/release/ctgrind
+//! ```
+//!
+//! Debug info lets the suppression in `ct/ctgrind.supp` match `aead::open` even where it is
+//! inlined. With `--canary` the binary also branches on a secret byte on purpose, which Valgrind
+//! must report; CI runs that too, to prove the instrumentation works.
+
+use core::hint::black_box;
+
+use chacha::aead::{ChaCha20Poly1305, XChaCha20Poly1305};
+use chacha::fallback_chacha20;
+use chacha::poly1305::{verify_tag, Poly1305};
+use chacha::FastChaCha20;
+use fast_chacha_ct::{declassify, running_on_valgrind, secret};
+
+fn main() {
+    let canary = std::env::args().any(|arg| arg == "--canary");
+    if !running_on_valgrind() {
+        eprintln!("ctgrind: not running under Valgrind, secrets are not tracked");
+    }
+
+    let mut key = [0x42u8; 32];
+    let mut message = [0x17u8; 1024];
+    let nonce = [0x24u8; 12];
+    let x_nonce = [0x24u8; 24];
+    let aad = b"public header";
+
+    // Stream cipher: asm path (whatever the CPU selects) and the Rust fallback.
+    secret(&key);
+    secret(&message);
+    FastChaCha20::new(&key, &nonce).apply_keystream(&mut message);
+    FastChaCha20::new(&key, &nonce).apply_keystream_pure(&mut message, 10);
+    let key_words: [u32; 8] = core::array::from_fn(|i| u32::from_ne_bytes([key[i]; 4]));
+    let mut block = [0u8; 64];
+    secret(&block);
+    fallback_chacha20::xor(&mut block, false, &key_words, &[1, 2, 3, 4], 10);
+    black_box((&message, &block));
+
+    // Poly1305 and the tag comparison.
+    let mut mac = Poly1305::new(&key);
+    mac.update(&message);
+    let tag = mac.finalize();
+    let other = [0u8; 16];
+    secret(&other);
+    black_box(declassify(verify_tag(&tag, &other)));
+
+    // AEADs: sealing, and opening with a valid and a forged tag. The branch on the
+    // verification verdict inside `aead::open` is public by design and suppressed.
+    let aead = ChaCha20Poly1305::new(&key);
+    let tag = aead.encrypt_in_place_detached(&nonce, aad, &mut message).unwrap();
+    black_box(aead.decrypt_in_place_detached(&nonce, aad, &mut message, &tag).is_ok());
+    black_box(aead.decrypt_in_place_detached(&nonce, aad, &mut message, &other).is_ok());
+
+    let xaead = XChaCha20Poly1305::new(&key);
+    let tag = xaead.encrypt_in_place_detached(&x_nonce, aad, &mut message).unwrap();
+    black_box(xaead.decrypt_in_place_detached(&x_nonce, aad, &mut message, &tag).is_ok());
+
+    if canary {
+        secret(&key);
+        if black_box(key[0]) == 0 {
+            key[1] = 1;
+        }
+        black_box(key);
+    }
+}
@@ -0,0 +1,127 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! dudect timing tests: each benchmark times an operation on a fixed input (left class)
+//! against a random or differently shaped one (right class), and reports Welch's t.
+//!
+//! ```sh
+//! cargo run --release -p fast_chacha_ct --bin dudect
+//! cargo run --release -p fast_chacha_ct --bin dudect -- --continuous tag_compare
+//! ```
+
+use chacha::aead::ChaCha20Poly1305;
+use chacha::fallback_chacha20;
+use chacha::poly1305::{verify_tag, Poly1305};
+use dudect_bencher::rand::{Rng, RngExt};
+use dudect_bencher::{ctbench_main, BenchRng, Class, CtRunner};
+
+/// Measurements per run, split at random between the two classes.
+const SAMPLES: usize = 100_000;
+
+/// Draws the class of each sample at random and prepares its input with `input`.
+///
+/// All inputs are generated before anything is timed, so that the cost of generating the
+/// random class does not leak into the measurements through the caches.
+fn inputs<T>(
+    rng: &mut BenchRng,
+    mut input: impl FnMut(Class, &mut BenchRng) -> T,
+) -> Vec<(Class, T)> {
+    (0..SAMPLES)
+        .map(|_| {
+            let class = if rng.random::<bool>() { Class::Left } else { Class::Right };
+            (class, input(class, rng))
+        })
+        .collect()
+}
+
+fn random<const N: usize>(rng: &mut BenchRng) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    rng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// `fallback_chacha20::xor` with a fixed key against random keys.
+fn fallback_xor(runner: &mut CtRunner, rng: &mut BenchRng) {
+    let counter = [1, 0, 0, 0];
+    let inputs = inputs(rng, |class, rng| {
+        let key: [u32; 8] = match class {
+            Class::Left => [0; 8],
+            Class::Right => core::array::from_fn(|_| rng.random()),
+        };
+        (key, random::<64>(rng))
+    });
+    for (class, (key, data)) in inputs {
+        runner.run_one(class, || {
+            let mut block = data;
+            fallback_chacha20::xor(&mut block, false, &key, &counter, 10);
+            block
+        });
+    }
+}
+
+/// Poly1305 with a fixed key and message against random ones.
+fn poly1305_mac(runner: &mut CtRunner, rng: &mut BenchRng) {
+    let inputs = inputs(rng, |class, rng| match class {
+        Class::Left => ([0u8; 32], [0u8; 64]),
+        Class::Right => (random::<32>(rng), random::<64>(rng)),
+    });
+    for (class, (key, msg)) in inputs {
+        runner.run_one(class, || {
+            let mut mac = Poly1305::new(&key);
+            mac.update(&msg);
+            mac.finalize()
+        });
+    }
+}
+
+/// Tag comparison of equal tags against tags that differ in a random byte.
+fn tag_compare(runner: &mut CtRunner, rng: &mut BenchRng) {
+    let inputs = inputs(rng, |class, rng| {
+        let a = random::<16>(rng);
+        let mut b = a;
+        if let Class::Right = class {
+            b[rng.random_range(0..16)] ^= rng.random_range(1..=255u8);
+        }
+        (a, b)
+    });
+    for (class, (a, b)) in inputs {
+        runner.run_one(class, || verify_tag(&a, &b));
+    }
+}
+
+/// ChaCha20-Poly1305 decryption of forged messages whose tag is wrong in the first byte
+/// against ones wrong in the last byte, under random keys.
+fn aead_open_forged(runner: &mut CtRunner, rng: &mut BenchRng) {
+    let nonce = [7u8; 12];
+    let inputs = inputs(rng, |class, rng| {
+        let aead = ChaCha20Poly1305::new(&random::<32>(rng));
+        let mut ciphertext = random::<64>(rng);
+        let mut tag = aead.encrypt_in_place_detached(&nonce, b"", &mut ciphertext).unwrap();
+        match class {
+            Class::Left => tag[0] ^= 1,
+            Class::Right => tag[15] ^= 1,
+        }
+        (aead, ciphertext, tag)
+    });
+    for (class, (aead, ciphertext, tag)) in inputs {
+        runner.run_one(class, || {
+            let mut buf = ciphertext;
+            aead.decrypt_in_place_detached(&nonce, b"", &mut buf, &tag)
+        });
+    }
+}
+
+ctbench_main!(fallback_xor, poly1305_mac, tag_compare, aead_open_forged);
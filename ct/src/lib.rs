@@ -0,0 +1,97 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Support code for the constant-time checks of `fast_chacha`.
+//!
+//! - `dudect` is a statistical timing test (Reparaz, Balasch and Verbauwhede, "dude, is my
+//!   code constant time?") built on `dudect-bencher`: each benchmark times an operation on
+//!   two classes of inputs, fixed and random, and reports Welch's t-statistic. `|t|` above
+//!   about 5 means the running time depends on the input.
+//! - `ctgrind` marks keys, messages and tags as undefined memory with the Memcheck client
+//!   requests below and runs the same operations under Valgrind, which then reports every
+//!   branch or memory index that depends on them (Adam Langley's ctgrind technique).
+//!
+//! Outside Valgrind the client requests are no-ops.
+
+/// `VG_USERREQ_TOOL_BASE('M', 'C')`, the first Memcheck request.
+const MEMCHECK_BASE: u64 = (b'M' as u64) << 24 | (b'C' as u64) << 16;
+const MAKE_MEM_UNDEFINED: u64 = MEMCHECK_BASE + 1;
+const MAKE_MEM_DEFINED: u64 = MEMCHECK_BASE + 2;
+const RUNNING_ON_VALGRIND: u64 = 0x1001;
+
+/// Issues a Valgrind client request; returns `default` when not running under Valgrind.
+#[cfg(target_arch = "x86_64")]
+fn client_request(default: u64, args: &[u64; 6]) -> u64 {
+    let result;
+    // The rotations of rdi add up to 128 bits and leave it unchanged; Valgrind recognises
+    // the sequence and handles the request at `xchg rbx, rbx`.
+    unsafe {
+        core::arch::asm!(
+            "rol rdi, 3", "rol rdi, 13", "rol rdi, 61", "rol rdi, 51", "xchg rbx, rbx",
+            in("rax") args.as_ptr(),
+            inout("rdx") default => result,
+        );
+    }
+    result
+}
+
+/// Issues a Valgrind client request; returns `default` when not running under Valgrind.
+#[cfg(target_arch = "aarch64")]
+fn client_request(default: u64, args: &[u64; 6]) -> u64 {
+    let result;
+    unsafe {
+        core::arch::asm!(
+            "ror x12, x12, #3", "ror x12, x12, #13", "ror x12, x12, #51", "ror x12, x12, #61",
+            "orr x10, x10, x10",
+            in("x4") args.as_ptr(),
+            inout("x3") default => result,
+        );
+    }
+    result
+}
+
+/// Valgrind does not run this target; every request returns `default`.
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn client_request(default: u64, _args: &[u64; 6]) -> u64 {
+    default
+}
+
+fn memcheck(request: u64, bytes: &[u8]) {
+    client_request(0, &[request, bytes.as_ptr() as u64, bytes.len() as u64, 0, 0, 0]);
+}
+
+/// Returns `true` when the process runs under Valgrind.
+pub fn running_on_valgrind() -> bool {
+    client_request(0, &[RUNNING_ON_VALGRIND, 0, 0, 0, 0, 0]) != 0
+}
+
+/// Marks `bytes` as secret: Memcheck reports any branch or address that depends on them.
+pub fn secret(bytes: &[u8]) {
+    memcheck(MAKE_MEM_UNDEFINED, bytes);
+}
+
+/// Marks `bytes` as public again, e.g. a result that is meant to be revealed.
+pub fn public(bytes: &[u8]) {
+    memcheck(MAKE_MEM_DEFINED, bytes);
+}
+
+/// Declassifies a value computed from secrets, such as a tag verification verdict.
+pub fn declassify(value: bool) -> bool {
+    let cell = [u8::from(value)];
+    public(&cell);
+    // Read back from memory, where the shadow state has just been changed.
+    unsafe { core::ptr::read_volatile(&cell[0]) != 0 }
+}
@@ -0,0 +1,189 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Differential fuzzing of the ChaCha20 backends against each other and against RustCrypto's
+//! `chacha20`.
+//!
+//! Every input is encrypted through:
+//! - `FastChaCha20::apply_keystream` in a single call (the asm path where available);
+//! - `apply_keystream`, `apply_keystream_b2b` and `apply_keystream_pure`, fed in the
+//!   fuzzer's chunk splits;
+//! - `fallback_chacha20::xor`, one block at a time;
+//!
+//! and all results must be identical to RustCrypto's. Lengths are biased towards the block
+//! and asm stride boundaries, and counters towards the 32-bit wrap, after which
+//! `ChaCha20_ctr32` continues at block 0 of the same nonce. RustCrypto refuses to produce
+//! block `u32::MAX`, so that block is only compared between fast_chacha's own backends.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use chacha20::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+use fast_chacha::{fallback_chacha20, FastChaCha20};
+use libfuzzer_sys::fuzz_target;
+
+/// Lengths around the block size and the 2, 4 and 8 block strides of the asm.
+const EDGE_LENGTHS: [usize; 20] =
+    [0, 1, 2, 63, 64, 65, 127, 128, 129, 191, 192, 193, 255, 256, 257, 511, 512, 513, 1023, 1024];
+
+#[derive(Arbitrary, Debug)]
+enum Length {
+    Edge(u8),
+    /// An edge length plus whole blocks, so the tail lands after the bulk loop.
+    EdgeAfterBlocks(u8, u8),
+    Any(u16),
+}
+
+#[derive(Arbitrary, Debug)]
+enum Counter {
+    /// Up to 255 blocks before the 32-bit counter wraps.
+    NearWrap(u8),
+    Any(u32),
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    key: [u8; 32],
+    nonce: [u8; 12],
+    counter: Counter,
+    length: Length,
+    /// Sizes of consecutive calls on the streaming paths.
+    splits: Vec<u16>,
+    /// Plaintext pattern, repeated to the length.
+    plaintext: Vec<u8>,
+}
+
+impl Input {
+    fn counter(&self) -> u32 {
+        match self.counter {
+            Counter::NearWrap(n) => u32::MAX - u32::from(n),
+            Counter::Any(c) => c,
+        }
+    }
+
+    fn len(&self) -> usize {
+        let edge = |i: u8| EDGE_LENGTHS[usize::from(i) % EDGE_LENGTHS.len()];
+        match self.length {
+            Length::Edge(i) => edge(i),
+            Length::EdgeAfterBlocks(i, blocks) => edge(i) + 64 * usize::from(blocks),
+            Length::Any(n) => usize::from(n),
+        }
+    }
+
+    fn plaintext(&self) -> Vec<u8> {
+        let len = self.len();
+        if self.plaintext.is_empty() {
+            return vec![0; len];
+        }
+        self.plaintext.iter().copied().cycle().take(len).collect()
+    }
+
+    /// Splits `len` into one chunk per split, of at most 1 KiB and possibly empty, followed
+    /// by the rest.
+    fn chunks(&self, len: usize) -> Vec<usize> {
+        let mut sizes = Vec::with_capacity(self.splits.len() + 1);
+        let mut rest = len;
+        for split in &self.splits {
+            let n = (usize::from(*split) % 1025).min(rest);
+            sizes.push(n);
+            rest -= n;
+        }
+        sizes.push(rest);
+        sizes
+    }
+}
+
+/// Applies `f` to consecutive chunks of `data` with the given sizes.
+fn chunked(data: &mut [u8], sizes: &[usize], mut f: impl FnMut(&mut [u8])) {
+    let mut start = 0;
+    for n in sizes {
+        f(&mut data[start..start + n]);
+        start += n;
+    }
+}
+
+/// RustCrypto's keystream applied block by block at `counter + i` modulo 2^32. Returns the
+/// byte range of block `u32::MAX`, which is left untouched.
+fn rustcrypto(
+    key: &[u8; 32],
+    nonce: &[u8; 12],
+    counter: u32,
+    data: &mut [u8],
+) -> Option<std::ops::Range<usize>> {
+    let mut cipher = chacha20::ChaCha20::new(key.into(), nonce.into());
+    let mut skipped = None;
+    for (i, block) in data.chunks_mut(64).enumerate() {
+        let ctr = counter.wrapping_add(i as u32);
+        if ctr == u32::MAX {
+            skipped = Some(i * 64..i * 64 + block.len());
+            continue;
+        }
+        cipher.seek(u64::from(ctr) * 64);
+        cipher.apply_keystream(block);
+    }
+    skipped
+}
+
+/// `fallback_chacha20::xor` over consecutive blocks.
+fn fallback_blocks(key: &[u8; 32], nonce: &[u8; 12], counter: u32, data: &mut [u8]) {
+    let word = |b: &[u8], i: usize| u32::from_le_bytes(b[4 * i..4 * i + 4].try_into().unwrap());
+    let key_words = core::array::from_fn(|i| word(key, i));
+    let mut ctr = [counter, word(nonce, 0), word(nonce, 1), word(nonce, 2)];
+    for block in data.chunks_mut(64) {
+        fallback_chacha20::xor(block, false, &key_words, &ctr, 10);
+        ctr[0] = ctr[0].wrapping_add(1);
+    }
+}
+
+fuzz_target!(|input: Input| {
+    let (key, nonce, counter) = (input.key, input.nonce, input.counter());
+    let plaintext = input.plaintext();
+    let sizes = input.chunks(plaintext.len());
+    let cipher = FastChaCha20::new_with_counter(key, nonce, counter);
+
+    let mut one_shot = plaintext.clone();
+    cipher.clone().apply_keystream(&mut one_shot);
+
+    let mut streamed = plaintext.clone();
+    let mut c = cipher.clone();
+    chunked(&mut streamed, &sizes, |chunk| c.apply_keystream(chunk));
+    assert_eq!(streamed, one_shot, "chunked apply_keystream");
+
+    let mut b2b = vec![0u8; plaintext.len()];
+    let mut c = cipher.clone();
+    let mut start = 0;
+    for n in &sizes {
+        let range = start..start + n;
+        c.apply_keystream_b2b(&plaintext[range.clone()], &mut b2b[range]).unwrap();
+        start += n;
+    }
+    assert_eq!(b2b, one_shot, "chunked apply_keystream_b2b");
+
+    let mut pure = plaintext.clone();
+    let mut c = cipher.clone();
+    chunked(&mut pure, &sizes, |chunk| c.apply_keystream_pure(chunk, 10));
+    assert_eq!(pure, one_shot, "chunked apply_keystream_pure");
+
+    let mut blocks = plaintext.clone();
+    fallback_blocks(&key, &nonce, counter, &mut blocks);
+    assert_eq!(blocks, one_shot, "fallback_chacha20::xor");
+
+    let mut reference = plaintext.clone();
+    if let Some(skipped) = rustcrypto(&key, &nonce, counter, &mut reference) {
+        reference[skipped.clone()].copy_from_slice(&one_shot[skipped]);
+    }
+    assert_eq!(reference, one_shot, "RustCrypto chacha20");
+});
@@ -0,0 +1,40 @@
+"""Type stubs for the fast_chacha extension module."""
+
+from typing import Optional, Union
+
+Buffer = Union[bytes, bytearray, memoryview]
+
+__version__: str
+
+class AuthenticationError(ValueError):
+    """The authentication tag did not verify; the data was forged or corrupted."""
+
+class FastChaCha20:
+    """ChaCha20 stream cipher (RFC 8439) with a 32-byte key and a 12-byte nonce."""
+
+    def __init__(self, key: bytes, nonce: bytes) -> None: ...
+    def apply_keystream(self, data: Buffer) -> Optional[bytes]:
+        """XOR `data` with the keystream: in place if writable, else return new bytes."""
+    def seek(self, position: int) -> None:
+        """Move to keystream byte `position`."""
+    @property
+    def position(self) -> int:
+        """Current keystream byte position."""
+
+class ChaCha20Poly1305:
+    """ChaCha20-Poly1305 (RFC 8439) with a 32-byte key and 12-byte nonces."""
+
+    def __init__(self, key: bytes) -> None: ...
+    def seal(self, nonce: bytes, data: Buffer, aad: Optional[bytes] = None) -> bytes:
+        """Encrypt `data` and return `ciphertext || tag`."""
+    def open(self, nonce: bytes, sealed: Buffer, aad: Optional[bytes] = None) -> bytes:
+        """Verify and decrypt `ciphertext || tag`; raise AuthenticationError on failure."""
+
+class XChaCha20Poly1305:
+    """XChaCha20-Poly1305 with a 32-byte key and 24-byte nonces, which may be random."""
+
+    def __init__(self, key: bytes) -> None: ...
+    def seal(self, nonce: bytes, data: Buffer, aad: Optional[bytes] = None) -> bytes:
+        """Encrypt `data` and return `ciphertext || tag`."""
+    def open(self, nonce: bytes, sealed: Buffer, aad: Optional[bytes] = None) -> bytes:
+        """Verify and decrypt `ciphertext || tag`; raise AuthenticationError on failure."""
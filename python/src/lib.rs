@@ -0,0 +1,283 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Python extension module `fast_chacha`, built with PyO3 and packaged with maturin
+//! (`pyproject.toml`).
+//!
+//! - `FastChaCha20(key, nonce)`: `apply_keystream(data)` encrypts writable buffers
+//!   (`bytearray`, writable `memoryview`, NumPy arrays of `uint8`) in place without
+//!   copying, and returns a new `bytes` for read-only ones; `seek(position)` and the
+//!   `position` attribute address the keystream by byte.
+//! - `ChaCha20Poly1305(key)` / `XChaCha20Poly1305(key)`: `seal(nonce, data, aad=None)`
+//!   returns `ciphertext || tag`, `open(nonce, sealed, aad=None)` returns the plaintext or
+//!   raises `AuthenticationError`.
+//!
+//! Buffers of [`DETACH_THRESHOLD`] bytes or more are processed with the GIL released, so
+//! other Python threads keep running. A buffer must not be modified by another thread
+//! while it is being processed.
+
+use chacha::aead::{self, KEY_BYTES, NONCE_BYTES, TAG_BYTES, X_NONCE_BYTES};
+use chacha::{Error, FastChaCha20};
+use pyo3::buffer::PyBuffer;
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// Buffers at least this long are processed with the GIL released.
+pub const DETACH_THRESHOLD: usize = 16 * 1024;
+
+create_exception!(
+    fast_chacha,
+    AuthenticationError,
+    PyValueError,
+    "The authentication tag did not verify; the data was forged or corrupted."
+);
+
+/// Maps a crate error to a Python exception.
+fn py_err(e: Error) -> PyErr {
+    match e {
+        Error::AuthenticationFailed => AuthenticationError::new_err(e.to_string()),
+        _ => PyValueError::new_err(e.to_string()),
+    }
+}
+
+/// Borrows `bytes` as an array of exactly `N` bytes.
+fn array<'a, const N: usize>(bytes: &'a [u8], what: &str) -> PyResult<&'a [u8; N]> {
+    bytes.try_into().map_err(|_| PyValueError::new_err(format!("{what} must be {N} bytes")))
+}
+
+/// Runs `f`, with the GIL released if `len` is at least [`DETACH_THRESHOLD`].
+fn run<T: Send>(py: Python<'_>, len: usize, f: impl FnOnce() -> T + Send) -> T {
+    if len >= DETACH_THRESHOLD {
+        py.detach(f)
+    } else {
+        f()
+    }
+}
+
+/// ChaCha20 stream cipher (RFC 8439) with a 32-byte key and a 12-byte nonce.
+#[pyclass(name = "FastChaCha20", module = "fast_chacha")]
+pub struct PyFastChaCha20 {
+    cipher: FastChaCha20,
+}
+
+#[pymethods]
+impl PyFastChaCha20 {
+    /// Creates a cipher at the start of the keystream.
+    ///
+    /// # Errors
+    /// `ValueError` if `key` is not 32 bytes or `nonce` is not 12 bytes long.
+    #[new]
+    fn new(key: &[u8], nonce: &[u8]) -> PyResult<Self> {
+        let cipher = FastChaCha20::new(
+            array::<KEY_BYTES>(key, "key")?,
+            array::<NONCE_BYTES>(nonce, "nonce")?,
+        );
+        Ok(Self { cipher })
+    }
+
+    /// XORs `data` with the next bytes of the keystream.
+    ///
+    /// Writable buffers are modified in place (`None` is returned); contiguous ones are not
+    /// copied. Read-only buffers such as `bytes` are left alone and the result is returned
+    /// as a new `bytes`.
+    ///
+    /// # Errors
+    /// `BufferError` if `data` does not export a byte buffer.
+    fn apply_keystream<'py>(
+        &mut self,
+        py: Python<'py>,
+        data: &Bound<'py, PyAny>,
+    ) -> PyResult<Option<Bound<'py, PyBytes>>> {
+        let buffer = PyBuffer::<u8>::get(data)?;
+        let cipher = &mut self.cipher;
+
+        if buffer.readonly() {
+            let mut out = buffer.to_vec(py)?;
+            run(py, out.len(), || cipher.apply_keystream(&mut out));
+            return Ok(Some(PyBytes::new(py, &out)));
+        }
+        match buffer.as_slice_ptr() {
+            Some(ptr) => {
+                // SAFETY: the buffer is writable and C-contiguous, and its exporter cannot
+                // resize or free it while `buffer` holds the export.
+                let data = unsafe { &mut *ptr.as_ptr() };
+                run(py, data.len(), || cipher.apply_keystream(data));
+            }
+            None => {
+                // Strided memoryview: gather, encrypt and scatter back.
+                let mut tmp = buffer.to_vec(py)?;
+                run(py, tmp.len(), || cipher.apply_keystream(&mut tmp));
+                buffer.copy_from_slice(py, &tmp)?;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Moves to keystream byte `position`.
+    fn seek(&mut self, position: u64) {
+        self.cipher.seek(position);
+    }
+
+    /// Current keystream byte position.
+    #[getter]
+    fn position(&self) -> u64 {
+        self.cipher.current_pos()
+    }
+}
+
+/// Copies `data`, encrypts it with `seal` and appends the tag.
+fn seal<'py>(
+    py: Python<'py>,
+    data: &PyBuffer<u8>,
+    seal: impl FnOnce(&mut [u8]) -> Result<[u8; TAG_BYTES], Error> + Send,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let len = data.len_bytes();
+    let mut out = vec![0u8; len + TAG_BYTES];
+    data.copy_to_slice(py, &mut out[..len])?;
+    let (buf, tag) = out.split_at_mut(len);
+    tag.copy_from_slice(&run(py, len, || seal(buf)).map_err(py_err)?);
+    Ok(PyBytes::new(py, &out))
+}
+
+/// Splits off the tag, copies the ciphertext and decrypts it with `open`.
+fn open<'py>(
+    py: Python<'py>,
+    sealed: &PyBuffer<u8>,
+    open: impl FnOnce(&mut [u8], &[u8; TAG_BYTES]) -> Result<(), Error> + Send,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mut out = sealed.to_vec(py)?;
+    let len = out.len().checked_sub(TAG_BYTES).ok_or_else(|| py_err(Error::InvalidLength))?;
+    let tag: [u8; TAG_BYTES] = out[len..].try_into().unwrap();
+    out.truncate(len);
+    run(py, len, || open(&mut out, &tag)).map_err(py_err)?;
+    Ok(PyBytes::new(py, &out))
+}
+
+/// ChaCha20-Poly1305 (RFC 8439) with a 32-byte key and 12-byte nonces.
+#[pyclass(name = "ChaCha20Poly1305", module = "fast_chacha", frozen)]
+pub struct PyChaCha20Poly1305 {
+    aead: aead::ChaCha20Poly1305,
+}
+
+#[pymethods]
+impl PyChaCha20Poly1305 {
+    /// # Errors
+    /// `ValueError` if `key` is not 32 bytes long.
+    #[new]
+    fn new(key: &[u8]) -> PyResult<Self> {
+        Ok(Self { aead: aead::ChaCha20Poly1305::new(array(key, "key")?) })
+    }
+
+    /// Encrypts `data` and returns `ciphertext || tag`.
+    ///
+    /// # Errors
+    /// `ValueError` if `nonce` is not 12 bytes long or `data` is too long.
+    #[pyo3(signature = (nonce, data, aad = None))]
+    fn seal<'py>(
+        &self,
+        py: Python<'py>,
+        nonce: &[u8],
+        data: PyBuffer<u8>,
+        aad: Option<&[u8]>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let nonce = array::<NONCE_BYTES>(nonce, "nonce")?;
+        let aad = aad.unwrap_or_default();
+        seal(py, &data, |buf| self.aead.encrypt_in_place_detached(nonce, aad, buf))
+    }
+
+    /// Verifies and decrypts `ciphertext || tag`, returning the plaintext.
+    ///
+    /// # Errors
+    /// `AuthenticationError` if the tag does not verify; `ValueError` if `nonce` is not 12
+    /// bytes long or `sealed` is shorter than a tag.
+    #[pyo3(signature = (nonce, sealed, aad = None))]
+    fn open<'py>(
+        &self,
+        py: Python<'py>,
+        nonce: &[u8],
+        sealed: PyBuffer<u8>,
+        aad: Option<&[u8]>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let nonce = array::<NONCE_BYTES>(nonce, "nonce")?;
+        let aad = aad.unwrap_or_default();
+        open(py, &sealed, |buf, tag| self.aead.decrypt_in_place_detached(nonce, aad, buf, tag))
+    }
+}
+
+/// XChaCha20-Poly1305 with a 32-byte key and 24-byte nonces, which may be random.
+#[pyclass(name = "XChaCha20Poly1305", module = "fast_chacha", frozen)]
+pub struct PyXChaCha20Poly1305 {
+    aead: aead::XChaCha20Poly1305,
+}
+
+#[pymethods]
+impl PyXChaCha20Poly1305 {
+    /// # Errors
+    /// `ValueError` if `key` is not 32 bytes long.
+    #[new]
+    fn new(key: &[u8]) -> PyResult<Self> {
+        Ok(Self { aead: aead::XChaCha20Poly1305::new(array(key, "key")?) })
+    }
+
+    /// Encrypts `data` and returns `ciphertext || tag`.
+    ///
+    /// # Errors
+    /// `ValueError` if `nonce` is not 24 bytes long or `data` is too long.
+    #[pyo3(signature = (nonce, data, aad = None))]
+    fn seal<'py>(
+        &self,
+        py: Python<'py>,
+        nonce: &[u8],
+        data: PyBuffer<u8>,
+        aad: Option<&[u8]>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let nonce = array::<X_NONCE_BYTES>(nonce, "nonce")?;
+        let aad = aad.unwrap_or_default();
+        seal(py, &data, |buf| self.aead.encrypt_in_place_detached(nonce, aad, buf))
+    }
+
+    /// Verifies and decrypts `ciphertext || tag`, returning the plaintext.
+    ///
+    /// # Errors
+    /// `AuthenticationError` if the tag does not verify; `ValueError` if `nonce` is not 24
+    /// bytes long or `sealed` is shorter than a tag.
+    #[pyo3(signature = (nonce, sealed, aad = None))]
+    fn open<'py>(
+        &self,
+        py: Python<'py>,
+        nonce: &[u8],
+        sealed: PyBuffer<u8>,
+        aad: Option<&[u8]>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let nonce = array::<X_NONCE_BYTES>(nonce, "nonce")?;
+        let aad = aad.unwrap_or_default();
+        open(py, &sealed, |buf, tag| self.aead.decrypt_in_place_detached(nonce, aad, buf, tag))
+    }
+}
+
+/// The `fast_chacha` Python module.
+#[pymodule]
+#[pyo3(name = "fast_chacha")]
+fn fast_chacha_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add("AuthenticationError", m.py().get_type::<AuthenticationError>())?;
+    m.add_class::<PyFastChaCha20>()?;
+    m.add_class::<PyChaCha20Poly1305>()?;
+    m.add_class::<PyXChaCha20Poly1305>()?;
+    Ok(())
+}
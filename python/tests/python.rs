@@ -0,0 +1,56 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Python extension tests
+ *
+ * Copies the extension module built for this test into a temporary directory as
+ * `fast_chacha.so` and runs `tests/test_fast_chacha.py` against it with the Python
+ * interpreter the module was built for (`$PYTHON`, default `python3`).
+ */
+
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// Directory holding this test and the library built for it (`target/<profile>/deps`).
+fn deps_dir() -> PathBuf {
+    std::env::current_exe().unwrap().parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn python_test_suite() {
+    let lib = deps_dir().join(format!("libfast_chacha_py{}", std::env::consts::DLL_SUFFIX));
+    assert!(lib.exists(), "{} not built", lib.display());
+    let dir = std::env::temp_dir().join(format!("fast_chacha_py_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(&lib, dir.join("fast_chacha.so")).unwrap();
+
+    let python = std::env::var("PYTHON").unwrap_or_else(|_| "python3".into());
+    let output = Command::new(python)
+        .args(["-m", "unittest", "-v", "test_fast_chacha"])
+        .current_dir(Path::new(MANIFEST_DIR).join("tests"))
+        .env("PYTHONPATH", &dir)
+        .env("PYTHONDONTWRITEBYTECODE", "1")
+        .output()
+        .expect("failed to run the Python interpreter");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
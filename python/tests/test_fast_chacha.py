@@ -0,0 +1,157 @@
+# Copyright 2025 sh0rch <sh0rch@iwl.dev>
+#
+# Licensed under the Apache License, Version 2.0 (the "License");
+# you may not use this file except in compliance with the License.
+# You may obtain a copy of the License at
+#
+#     http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing, software
+# distributed under the License is distributed on an "AS IS" BASIS,
+# WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+# See the License for the specific language governing permissions and
+# limitations under the License.
+
+"""Tests for the fast_chacha extension module.
+
+Run with `python -m unittest` from this directory once the module is importable, e.g.
+after `maturin develop`; `cargo test -p fast_chacha_python` does this automatically.
+"""
+
+import sys
+import threading
+import unittest
+
+import fast_chacha
+from fast_chacha import (
+    AuthenticationError,
+    ChaCha20Poly1305,
+    FastChaCha20,
+    XChaCha20Poly1305,
+)
+
+SUNSCREEN = (
+    b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for "
+    b"the future, sunscreen would be it."
+)
+
+
+class StreamCipherTest(unittest.TestCase):
+    key = bytes(range(32))
+    nonce = bytes.fromhex("000000000000004a00000000")
+
+    def test_rfc8439_vector(self):
+        # RFC 8439 §2.4.2: block counter 1.
+        cipher = FastChaCha20(self.key, self.nonce)
+        cipher.seek(64)
+        out = cipher.apply_keystream(SUNSCREEN)
+        self.assertEqual(
+            out,
+            bytes.fromhex(
+                "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b"
+                "f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8"
+                "07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736"
+                "5af90bbf74a35be6b40b8eedf2785e42874d"
+            ),
+        )
+        self.assertEqual(cipher.position, 64 + len(SUNSCREEN))
+
+    def test_in_place_on_writable_buffers(self):
+        expected = FastChaCha20(self.key, self.nonce).apply_keystream(bytes(1000))
+
+        data = bytearray(1000)
+        self.assertIsNone(FastChaCha20(self.key, self.nonce).apply_keystream(data))
+        self.assertEqual(data, expected)
+
+        # A memoryview slice writes through to the underlying bytearray.
+        data = bytearray(1100)
+        cipher = FastChaCha20(self.key, self.nonce)
+        cipher.apply_keystream(memoryview(data)[50:1050])
+        self.assertEqual(data[50:1050], expected)
+        self.assertEqual(data[:50] + data[1050:], bytes(100))
+
+        # Strided views are supported, though not zero-copy.
+        data = bytearray(2000)
+        FastChaCha20(self.key, self.nonce).apply_keystream(memoryview(data)[::2])
+        self.assertEqual(data[::2], expected)
+        self.assertEqual(data[1::2], bytes(1000))
+
+    def test_split_calls_and_seek(self):
+        whole = FastChaCha20(self.key, self.nonce).apply_keystream(bytes(100_000))
+        cipher = FastChaCha20(self.key, self.nonce)
+        data = bytearray(100_000)
+        for start in range(0, len(data), 777):
+            cipher.apply_keystream(memoryview(data)[start : start + 777])
+        self.assertEqual(data, whole)
+
+        cipher.seek(12_345)
+        self.assertEqual(cipher.apply_keystream(bytes(100)), whole[12_345:12_445])
+
+    def test_invalid_arguments(self):
+        with self.assertRaises(ValueError):
+            FastChaCha20(bytes(31), self.nonce)
+        with self.assertRaises(ValueError):
+            FastChaCha20(self.key, bytes(24))
+        with self.assertRaises(TypeError):
+            FastChaCha20(self.key, self.nonce).apply_keystream("text")
+
+    def test_releases_the_gil(self):
+        # With a huge switch interval the main thread keeps the GIL until it blocks, so the
+        # woken thread can only run while apply_keystream has released it.
+        ran = []
+        ready = threading.Event()
+        thread = threading.Thread(target=lambda: ready.wait() and ran.append(True))
+        interval = sys.getswitchinterval()
+        sys.setswitchinterval(1000)
+        try:
+            thread.start()
+            data = bytearray(64 << 20)
+            ready.set()
+            FastChaCha20(self.key, self.nonce).apply_keystream(data)
+            ran_during_call = bool(ran)
+        finally:
+            sys.setswitchinterval(interval)
+            thread.join()
+        self.assertTrue(ran_during_call)
+
+
+class AeadTest(unittest.TestCase):
+    key = bytes(range(0x80, 0xA0))
+
+    def test_rfc8439_vector(self):
+        # RFC 8439 §2.8.2.
+        nonce = bytes.fromhex("070000004041424344454647")
+        aad = bytes.fromhex("50515253c0c1c2c3c4c5c6c7")
+        aead = ChaCha20Poly1305(self.key)
+
+        sealed = aead.seal(nonce, SUNSCREEN, aad)
+        self.assertEqual(sealed[:16], bytes.fromhex("d31a8d34648e60db7b86afbc53ef7ec2"))
+        self.assertEqual(sealed[-16:], bytes.fromhex("1ae10b594f09e26a7e902ecbd0600691"))
+        self.assertEqual(aead.open(nonce, sealed, aad), SUNSCREEN)
+        self.assertEqual(aead.open(nonce, bytearray(sealed), aad), SUNSCREEN)
+
+        with self.assertRaises(AuthenticationError):
+            aead.open(nonce, sealed, b"other")
+        with self.assertRaises(AuthenticationError):
+            aead.open(nonce, sealed[:-1] + bytes([sealed[-1] ^ 1]), aad)
+        with self.assertRaises(ValueError):
+            aead.open(nonce, sealed[:15], aad)
+
+    def test_xchacha_round_trip(self):
+        aead = XChaCha20Poly1305(self.key)
+        nonce = bytes(range(24))
+        data = bytes(range(256)) * 1000
+
+        sealed = aead.seal(nonce, memoryview(data))
+        self.assertEqual(len(sealed), len(data) + 16)
+        self.assertEqual(aead.open(nonce, sealed), data)
+        self.assertTrue(issubclass(AuthenticationError, ValueError))
+        with self.assertRaises(ValueError):
+            aead.seal(bytes(12), data)
+
+    def test_version(self):
+        self.assertRegex(fast_chacha.__version__, r"^\d+\.\d+\.\d+$")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,237 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Adiantum: tweakable, length-preserving wide-block encryption for disk sectors.
+//!
+//! Adiantum (Crowley & Biggers, 2018) encrypts a whole sector as one block, so changing any
+//! plaintext bit changes the entire ciphertext sector, without any ciphertext expansion.
+//! The sector is split into a bulk part `P_L` and its last 16 bytes `P_R`:
+//!
+//! 1. `P_M = P_R + H(T, P_L)` (addition mod 2^128)
+//! 2. `C_M = AES-256(K_E, P_M)`
+//! 3. `C_L = P_L ^ XChaCha12(K, nonce = C_M || 1 || 0^7)`
+//! 4. `C_R = C_M - H(T, C_L)`
+//!
+//! where `H(T, M) = Poly1305_{K_T}(le128(8 * |M|) || T) + Poly1305_{K_M}(NH(M))`, both
+//! Poly1305 instances used without the final `s` addition. The AES key, `K_T`, `K_M` and
+//! the NH key are derived from the XChaCha keystream with the nonce `1 || 0^23`.
+//!
+//! The XChaCha stream uses the crate's round-count support; the 20-round variant goes
+//! through the assembly backend. Requires the `adiantum` feature (AES comes from the
+//! `aes` crate).
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::adiantum::Adiantum;
+//!
+//! let cipher = Adiantum::new(&[7u8; 32]);
+//! let tweak = 42u64.to_le_bytes();
+//! let mut sector = [0u8; 4096];
+//!
+//! cipher.encrypt(&tweak, &mut sector).unwrap();
+//! cipher.decrypt(&tweak, &mut sector).unwrap();
+//! assert_eq!(sector, [0u8; 4096]);
+//! ```
+
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use aes::{Aes256, Block};
+
+use crate::poly1305::Poly1305;
+use crate::{Error, FastChaCha20};
+
+/// Size of the key, in bytes.
+pub const KEY_BYTES: usize = 32;
+/// Smallest sector Adiantum can encrypt, in bytes.
+pub const MIN_SECTOR_BYTES: usize = 16;
+
+/// Size of the message unit NH processes at once, in bytes.
+const NH_MESSAGE_BYTES: usize = 1024;
+/// Number of NH key words: one message's worth plus the offsets of the 3 extra passes.
+const NH_KEY_WORDS: usize = NH_MESSAGE_BYTES / 4 + 2 * 2 * 3;
+/// Size of the derived subkeys (AES key, `K_T`, `K_M`, NH key), in bytes.
+const DERIVED_KEY_BYTES: usize = 32 + 16 + 16 + NH_KEY_WORDS * 4;
+
+/// Reads a little-endian `u32` from the first four bytes of `b`.
+#[inline(always)]
+fn le32(b: &[u8]) -> u32 {
+    u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+}
+
+/// NH with 4 passes over one message chunk of at most 1 KiB, zero-padded to 16 bytes.
+fn nh(key: &[u32; NH_KEY_WORDS], message: &[u8]) -> [u8; 32] {
+    let mut sums = [0u64; 4];
+
+    for (i, unit) in message.chunks(16).enumerate() {
+        let mut m = [0u8; 16];
+        m[..unit.len()].copy_from_slice(unit);
+        let (m0, m1, m2, m3) = (le32(&m[0..]), le32(&m[4..]), le32(&m[8..]), le32(&m[12..]));
+
+        // Pass `p` uses the key shifted by 4 * p words.
+        for (pass, sum) in sums.iter_mut().enumerate() {
+            let k = &key[4 * (i + pass)..];
+            *sum = sum
+                .wrapping_add(m0.wrapping_add(k[0]) as u64 * m2.wrapping_add(k[2]) as u64)
+                .wrapping_add(m1.wrapping_add(k[1]) as u64 * m3.wrapping_add(k[3]) as u64);
+        }
+    }
+
+    let mut out = [0u8; 32];
+    for (o, sum) in out.chunks_exact_mut(8).zip(sums) {
+        o.copy_from_slice(&sum.to_le_bytes());
+    }
+    out
+}
+
+/// Converts the four little-endian words returned by [`Poly1305::reduce`] into an integer.
+#[inline(always)]
+fn words_to_u128(words: [u32; 4]) -> u128 {
+    words.iter().rev().fold(0u128, |acc, &w| acc << 32 | w as u128)
+}
+
+/// Adiantum with a 256-bit key.
+#[derive(Clone)]
+pub struct Adiantum {
+    key: [u8; KEY_BYTES],
+    double_rounds: usize,
+    aes: Aes256,
+    /// Poly1305 with `K_T` and no data, cloned for every tweak hash.
+    header_hash: Poly1305,
+    /// Poly1305 with `K_M` and no data, cloned for every message hash.
+    message_hash: Poly1305,
+    nh_key: [u32; NH_KEY_WORDS],
+}
+
+impl Adiantum {
+    /// Creates Adiantum with XChaCha12, the standard variant.
+    ///
+    /// # Arguments
+    /// * `key` - 32-byte key.
+    pub fn new(key: &[u8; KEY_BYTES]) -> Self {
+        Self::new_with_double_rounds(key, 6)
+    }
+
+    /// Creates Adiantum with an XChaCha variant of `2 * double_rounds` rounds
+    /// (4 for XChaCha8, 6 for XChaCha12, 10 for XChaCha20).
+    ///
+    /// # Arguments
+    /// * `key` - 32-byte key.
+    /// * `double_rounds` - Number of ChaCha double rounds.
+    pub fn new_with_double_rounds(key: &[u8; KEY_BYTES], double_rounds: usize) -> Self {
+        let mut nonce = [0u8; 24];
+        nonce[0] = 1;
+        let mut derived = [0u8; DERIVED_KEY_BYTES];
+        Self::xor_stream(key, &nonce, double_rounds, &mut derived);
+
+        let (aes_key, rest) = derived.split_at(32);
+        let (header_key, rest) = rest.split_at(16);
+        let (message_key, nh_key) = rest.split_at(16);
+
+        Self {
+            key: *key,
+            double_rounds,
+            aes: Aes256::new(aes_key.into()),
+            header_hash: Poly1305::from_r(header_key.try_into().unwrap()),
+            message_hash: Poly1305::from_r(message_key.try_into().unwrap()),
+            nh_key: core::array::from_fn(|i| le32(&nh_key[i * 4..])),
+        }
+    }
+
+    /// XORs `data` with the XChaCha keystream for `nonce`.
+    fn xor_stream(key: &[u8; KEY_BYTES], nonce: &[u8; 24], double_rounds: usize, data: &mut [u8]) {
+        let mut cipher = FastChaCha20::new_xchacha(key, nonce, double_rounds);
+        if double_rounds == 10 {
+            cipher.apply_keystream(data);
+        } else {
+            cipher.apply_keystream_pure(data, double_rounds);
+        }
+    }
+
+    /// Computes `H(T, M)`.
+    fn hash(&self, tweak: &[u8], bulk: &[u8]) -> u128 {
+        let mut header = self.header_hash.clone();
+        header.update(&(8 * bulk.len() as u128).to_le_bytes());
+        header.update(tweak);
+
+        let mut message = self.message_hash.clone();
+        for chunk in bulk.chunks(NH_MESSAGE_BYTES) {
+            message.update(&nh(&self.nh_key, chunk));
+        }
+
+        words_to_u128(header.reduce()).wrapping_add(words_to_u128(message.reduce()))
+    }
+
+    /// Splits a sector into its bulk part and its last 16 bytes.
+    fn split(sector: &mut [u8]) -> Result<(&mut [u8], &mut [u8; 16]), Error> {
+        if sector.len() < MIN_SECTOR_BYTES {
+            return Err(Error::InvalidLength);
+        }
+        let (bulk, right) = sector.split_at_mut(sector.len() - 16);
+        Ok((bulk, right.try_into().unwrap()))
+    }
+
+    /// Encrypts a sector in place.
+    ///
+    /// # Arguments
+    /// * `tweak` - Per-sector tweak, usually the little-endian sector number. Any length is
+    ///   accepted; the Linux implementation uses 32 bytes.
+    /// * `sector` - Sector of at least [`MIN_SECTOR_BYTES`] bytes.
+    ///
+    /// # Errors
+    /// [`Error::InvalidLength`] if `sector` is shorter than [`MIN_SECTOR_BYTES`].
+    pub fn encrypt(&self, tweak: &[u8], sector: &mut [u8]) -> Result<(), Error> {
+        let (bulk, right) = Self::split(sector)?;
+
+        let pm = u128::from_le_bytes(*right).wrapping_add(self.hash(tweak, bulk));
+        let mut block = Block::from(pm.to_le_bytes());
+        self.aes.encrypt_block(&mut block);
+        let cm: [u8; 16] = block.into();
+
+        let mut nonce = [0u8; 24];
+        nonce[..16].copy_from_slice(&cm);
+        nonce[16] = 1;
+        Self::xor_stream(&self.key, &nonce, self.double_rounds, bulk);
+
+        let cr = u128::from_le_bytes(cm).wrapping_sub(self.hash(tweak, bulk));
+        *right = cr.to_le_bytes();
+        Ok(())
+    }
+
+    /// Decrypts a sector in place.
+    ///
+    /// # Arguments
+    /// * `tweak` - The tweak used for encryption.
+    /// * `sector` - Encrypted sector of at least [`MIN_SECTOR_BYTES`] bytes.
+    ///
+    /// # Errors
+    /// [`Error::InvalidLength`] if `sector` is shorter than [`MIN_SECTOR_BYTES`].
+    pub fn decrypt(&self, tweak: &[u8], sector: &mut [u8]) -> Result<(), Error> {
+        let (bulk, right) = Self::split(sector)?;
+
+        let cm = u128::from_le_bytes(*right).wrapping_add(self.hash(tweak, bulk)).to_le_bytes();
+        let mut nonce = [0u8; 24];
+        nonce[..16].copy_from_slice(&cm);
+        nonce[16] = 1;
+        Self::xor_stream(&self.key, &nonce, self.double_rounds, bulk);
+
+        let mut block = Block::from(cm);
+        self.aes.decrypt_block(&mut block);
+        let pm = u128::from_le_bytes(block.into());
+
+        *right = pm.wrapping_sub(self.hash(tweak, bulk)).to_le_bytes();
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! ChaCha20-Poly1305 AEAD (RFC 8439 §2.8) and its XChaCha20 variant on top of [`FastChaCha20`].
+//!
+//! The Poly1305 key is the first 32 bytes of keystream block 0, the message is encrypted
+//! starting at block 1, and the tag covers `aad || pad16 || ciphertext || pad16 ||
+//! le64(aad_len) || le64(ciphertext_len)`. Encryption and decryption work in place with a
+//! detached 16-byte tag, which is what record-oriented protocols need.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::aead::ChaCha20Poly1305;
+//!
+//! let aead = ChaCha20Poly1305::new(&[1u8; 32]);
+//! let nonce = [2u8; 12];
+//! let mut buf = *b"attack at dawn";
+//!
+//! let tag = aead.encrypt_in_place_detached(&nonce, b"header", &mut buf).unwrap();
+//! aead.decrypt_in_place_detached(&nonce, b"header", &mut buf, &tag).unwrap();
+//! assert_eq!(&buf, b"attack at dawn");
+//! ```
+
+use crate::nonce::NonceSequence;
+use crate::poly1305::{verify_tag, Poly1305};
+use crate::{Error, FastChaCha20};
+
+/// Size of the key, in bytes.
+pub const KEY_BYTES: usize = 32;
+/// Size of the nonce, in bytes.
+pub const NONCE_BYTES: usize = 12;
+/// Size of the XChaCha20-Poly1305 nonce, in bytes.
+pub const X_NONCE_BYTES: usize = 24;
+/// Size of the authentication tag, in bytes.
+pub const TAG_BYTES: usize = 16;
+
+/// Largest message accepted for one nonce: the 32-bit block counter starts at 1.
+const MAX_MESSAGE_LEN: u64 = ((1u64 << 32) - 1) * 64;
+
+/// Derives the Poly1305 key from block 0, positions `cipher` at block 1 and absorbs the AAD.
+fn begin(mut cipher: FastChaCha20, aad: &[u8]) -> (FastChaCha20, Poly1305) {
+    let mut poly_key = [0u8; 32];
+    cipher.apply_keystream(&mut poly_key);
+    cipher.set_counter(1);
+
+    let mut mac = Poly1305::new(&poly_key);
+    mac.update(aad);
+    mac.pad();
+    (cipher, mac)
+}
+
+/// Absorbs the ciphertext and the length block, and returns the tag.
+fn finish(mut mac: Poly1305, aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_BYTES] {
+    mac.update(ciphertext);
+    mac.pad();
+    mac.update(&(aad.len() as u64).to_le_bytes());
+    mac.update(&(ciphertext.len() as u64).to_le_bytes());
+    mac.finalize()
+}
+
+/// Encrypts `buf` in place with a freshly keyed cipher and returns the tag.
+fn seal(cipher: FastChaCha20, aad: &[u8], buf: &mut [u8]) -> Result<[u8; TAG_BYTES], Error> {
+    if buf.len() as u64 > MAX_MESSAGE_LEN {
+        return Err(Error::InvalidLength);
+    }
+    let (mut cipher, mac) = begin(cipher, aad);
+    cipher.apply_keystream(buf);
+    Ok(finish(mac, aad, buf))
+}
+
+/// Verifies the tag and decrypts `buf` in place with a freshly keyed cipher.
+fn open(
+    cipher: FastChaCha20,
+    aad: &[u8],
+    buf: &mut [u8],
+    tag: &[u8; TAG_BYTES],
+) -> Result<(), Error> {
+    if buf.len() as u64 > MAX_MESSAGE_LEN {
+        return Err(Error::InvalidLength);
+    }
+    let (mut cipher, mac) = begin(cipher, aad);
+    if !verify_tag(&finish(mac, aad, buf), tag) {
+        return Err(Error::AuthenticationFailed);
+    }
+    cipher.apply_keystream(buf);
+    Ok(())
+}
+
+/// ChaCha20-Poly1305 with a 96-bit nonce, as specified in RFC 8439.
+#[derive(Clone)]
+pub struct ChaCha20Poly1305 {
+    key: [u8; KEY_BYTES],
+}
+
+impl ChaCha20Poly1305 {
+    /// Creates the AEAD from a 32-byte key.
+    ///
+    /// # Arguments
+    /// * `key` - 32-byte key.
+    pub fn new(key: &[u8; KEY_BYTES]) -> Self {
+        Self { key: *key }
+    }
+
+    /// Encrypts `buf` in place and returns the tag.
+    ///
+    /// # Arguments
+    /// * `nonce` - 12-byte nonce; must never repeat for the same key.
+    /// * `aad` - Additional data authenticated but not encrypted.
+    /// * `buf` - Plaintext, replaced with the ciphertext.
+    ///
+    /// # Errors
+    /// [`Error::InvalidLength`] if `buf` is longer than the 256 GiB the block counter allows.
+    pub fn encrypt_in_place_detached(
+        &self,
+        nonce: &[u8; NONCE_BYTES],
+        aad: &[u8],
+        buf: &mut [u8],
+    ) -> Result<[u8; TAG_BYTES], Error> {
+        seal(FastChaCha20::new(&self.key, nonce), aad, buf)
+    }
+
+    /// Encrypts `buf` in place under the next nonce of `nonces`, and returns the nonce and
+    /// the tag.
+    ///
+    /// With the `nonce-registry` feature, debug builds record the (key, nonce) pair and
+    /// panic if it was sealed through this method before, e.g. because two sequences with
+    /// the same prefix share the key.
+    ///
+    /// # Arguments
+    /// * `nonces` - Sequence supplying the nonce.
+    /// * `aad` - Additional data authenticated but not encrypted.
+    /// * `buf` - Plaintext, replaced with the ciphertext.
+    ///
+    /// # Errors
+    /// - Whatever `nonces` reports, e.g. [`Error::NonceExhausted`].
+    /// - [`Error::InvalidLength`] if `buf` is longer than the 256 GiB the block counter
+    ///   allows.
+    pub fn encrypt_next_in_place_detached(
+        &self,
+        nonces: &mut impl NonceSequence<NONCE_BYTES>,
+        aad: &[u8],
+        buf: &mut [u8],
+    ) -> Result<([u8; NONCE_BYTES], [u8; TAG_BYTES]), Error> {
+        let nonce = nonces.next_nonce()?;
+        #[cfg(feature = "nonce-registry")]
+        crate::nonce::registry::record(&self.key, &nonce);
+        let tag = seal(FastChaCha20::new(&self.key, &nonce), aad, buf)?;
+        Ok((nonce, tag))
+    }
+
+    /// Verifies the tag and decrypts `buf` in place.
+    ///
+    /// On failure `buf` is left untouched.
+    ///
+    /// # Arguments
+    /// * `nonce` - 12-byte nonce used for encryption.
+    /// * `aad` - Additional data supplied at encryption.
+    /// * `buf` - Ciphertext, replaced with the plaintext.
+    /// * `tag` - Tag returned by encryption.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidLength`] if `buf` is longer than any valid ciphertext.
+    /// - [`Error::AuthenticationFailed`] if the tag does not verify.
+    pub fn decrypt_in_place_detached(
+        &self,
+        nonce: &[u8; NONCE_BYTES],
+        aad: &[u8],
+        buf: &mut [u8],
+        tag: &[u8; TAG_BYTES],
+    ) -> Result<(), Error> {
+        open(FastChaCha20::new(&self.key, nonce), aad, buf, tag)
+    }
+}
+
+/// XChaCha20-Poly1305: the same construction keyed through HChaCha20 with a 192-bit nonce,
+/// compatible with libsodium's `crypto_aead_xchacha20poly1305_ietf`.
+///
+/// The nonce is long enough to be chosen at random for every message.
+#[derive(Clone)]
+pub struct XChaCha20Poly1305 {
+    key: [u8; KEY_BYTES],
+}
+
+impl XChaCha20Poly1305 {
+    /// Creates the AEAD from a 32-byte key.
+    ///
+    /// # Arguments
+    /// * `key` - 32-byte key.
+    pub fn new(key: &[u8; KEY_BYTES]) -> Self {
+        Self { key: *key }
+    }
+
+    /// Encrypts `buf` in place and returns the tag.
+    ///
+    /// # Arguments
+    /// * `nonce` - 24-byte nonce; must never repeat for the same key.
+    /// * `aad` - Additional data authenticated but not encrypted.
+    /// * `buf` - Plaintext, replaced with the ciphertext.
+    ///
+    /// # Errors
+    /// [`Error::InvalidLength`] if `buf` is longer than the 256 GiB the block counter allows.
+    pub fn encrypt_in_place_detached(
+        &self,
+        nonce: &[u8; X_NONCE_BYTES],
+        aad: &[u8],
+        buf: &mut [u8],
+    ) -> Result<[u8; TAG_BYTES], Error> {
+        seal(FastChaCha20::new_xchacha20(&self.key, nonce), aad, buf)
+    }
+
+    /// Encrypts `buf` in place under the next nonce of `nonces`, and returns the nonce and
+    /// the tag.
+    ///
+    /// With the `nonce-registry` feature, debug builds record the (key, nonce) pair and
+    /// panic if it was sealed through this method before, e.g. because a custom sequence
+    /// repeats itself.
+    ///
+    /// # Arguments
+    /// * `nonces` - Sequence supplying the nonce.
+    /// * `aad` - Additional data authenticated but not encrypted.
+    /// * `buf` - Plaintext, replaced with the ciphertext.
+    ///
+    /// # Errors
+    /// - Whatever `nonces` reports, e.g. [`Error::NonceExhausted`].
+    /// - [`Error::InvalidLength`] if `buf` is longer than the 256 GiB the block counter
+    ///   allows.
+    pub fn encrypt_next_in_place_detached(
+        &self,
+        nonces: &mut impl NonceSequence<X_NONCE_BYTES>,
+        aad: &[u8],
+        buf: &mut [u8],
+    ) -> Result<([u8; X_NONCE_BYTES], [u8; TAG_BYTES]), Error> {
+        let nonce = nonces.next_nonce()?;
+        #[cfg(feature = "nonce-registry")]
+        crate::nonce::registry::record(&self.key, &nonce);
+        let tag = seal(FastChaCha20::new_xchacha20(&self.key, &nonce), aad, buf)?;
+        Ok((nonce, tag))
+    }
+
+    /// Verifies the tag and decrypts `buf` in place.
+    ///
+    /// On failure `buf` is left untouched.
+    ///
+    /// # Arguments
+    /// * `nonce` - 24-byte nonce used for encryption.
+    /// * `aad` - Additional data supplied at encryption.
+    /// * `buf` - Ciphertext, replaced with the plaintext.
+    /// * `tag` - Tag returned by encryption.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidLength`] if `buf` is longer than any valid ciphertext.
+    /// - [`Error::AuthenticationFailed`] if the tag does not verify.
+    pub fn decrypt_in_place_detached(
+        &self,
+        nonce: &[u8; X_NONCE_BYTES],
+        aad: &[u8],
+        buf: &mut [u8],
+        tag: &[u8; TAG_BYTES],
+    ) -> Result<(), Error> {
+        open(FastChaCha20::new_xchacha20(&self.key, nonce), aad, buf, tag)
+    }
+}
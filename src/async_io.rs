@@ -0,0 +1,235 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Tokio `AsyncRead`/`AsyncWrite` adapters around [`FastChaCha20`].
+//!
+//! Enabled with the `tokio` feature. [`AsyncChaChaWriter`] buffers and encrypts data on its
+//! way to the inner writer and [`AsyncChaChaReader`] decrypts data coming from the inner reader, so
+//! sockets, pipes and files can be wrapped transparently.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::async_io::{AsyncChaChaReader, AsyncChaChaWriter};
+//! use fast_chacha::FastChaCha20;
+//! use tokio::io::{AsyncReadExt, AsyncWriteExt};
+//!
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let key = [1u8; 32];
+//! let nonce = [2u8; 12];
+//! let (client, server) = tokio::io::duplex(64);
+//!
+//! let mut writer = AsyncChaChaWriter::new(client, FastChaCha20::new(&key, &nonce));
+//! let mut reader = AsyncChaChaReader::new(server, FastChaCha20::new(&key, &nonce));
+//!
+//! writer.write_all(b"hello over the wire").await.unwrap();
+//! writer.flush().await.unwrap();
+//! let mut buf = [0u8; 19];
+//! reader.read_exact(&mut buf).await.unwrap();
+//! assert_eq!(&buf, b"hello over the wire");
+//! # });
+//! ```
+
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
+use std::boxed::Box;
+use std::io;
+use std::vec;
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::io::BUFFER_SIZE;
+use crate::FastChaCha20;
+
+/// Encrypts all data written to it and forwards the ciphertext to an inner async writer.
+///
+/// Like [`ChaChaWriter`](crate::io::ChaChaWriter), plaintext accepted by `poll_write` is
+/// collected in a buffer of [`BUFFER_SIZE`] bytes and encrypted with a single keystream call
+/// when the buffer is drained into the inner writer. Ciphertext the inner writer has not
+/// taken yet, because it returned `Pending`, an error or a short write, is kept across polls
+/// and never encrypted again. Call `flush` or `shutdown` to make sure all buffered data
+/// reaches the inner writer.
+pub struct AsyncChaChaWriter<W> {
+    inner: W,
+    cipher: FastChaCha20,
+    buf: Box<[u8]>,
+    /// Start of the ciphertext in `buf` not yet taken by the inner writer.
+    pos: usize,
+    /// End of the ciphertext in `buf`; plaintext waiting to be encrypted follows it.
+    encrypted: usize,
+    /// End of the data in `buf`.
+    len: usize,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncChaChaWriter<W> {
+    /// Creates a new encrypting writer.
+    ///
+    /// # Arguments
+    /// * `inner` - Writer receiving the ciphertext.
+    /// * `cipher` - Cipher positioned where encryption should start.
+    pub fn new(inner: W, cipher: FastChaCha20) -> Self {
+        Self {
+            inner,
+            cipher,
+            buf: vec![0u8; BUFFER_SIZE].into_boxed_slice(),
+            pos: 0,
+            encrypted: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner writer.
+    ///
+    /// Writing to it directly bypasses encryption and the internal buffer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns a reference to the cipher.
+    ///
+    /// Its position only covers data that has been encrypted out of the internal buffer.
+    pub fn cipher(&self) -> &FastChaCha20 {
+        &self.cipher
+    }
+
+    /// Returns the inner writer and the cipher.
+    ///
+    /// Buffered data is discarded; flush the writer first to keep it.
+    pub fn into_inner(self) -> (W, FastChaCha20) {
+        (self.inner, self.cipher)
+    }
+
+    /// Encrypts the buffered plaintext and writes all buffered ciphertext to the inner writer.
+    fn poll_flush_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.cipher.apply_keystream(&mut self.buf[self.encrypted..self.len]);
+        self.encrypted = self.len;
+
+        while self.pos < self.len {
+            match Pin::new(&mut self.inner).poll_write(cx, &self.buf[self.pos..self.len]) {
+                Poll::Ready(Ok(0)) => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write the buffered data",
+                    )));
+                }
+                Poll::Ready(Ok(n)) => self.pos += n,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        self.pos = 0;
+        self.encrypted = 0;
+        self.len = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncChaChaWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        data: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if data.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        if this.len == this.buf.len() {
+            ready!(this.poll_flush_buf(cx))?;
+        }
+
+        let n = data.len().min(this.buf.len() - this.len);
+        this.buf[this.len..this.len + n].copy_from_slice(&data[..n]);
+        this.len += n;
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_flush_buf(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_flush_buf(cx))?;
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+/// Decrypts all data read from an inner async reader.
+///
+/// Bytes are decrypted in place in the caller's buffer as soon as the inner reader
+/// produces them, so no ciphertext is ever held back.
+pub struct AsyncChaChaReader<R> {
+    inner: R,
+    cipher: FastChaCha20,
+}
+
+impl<R: AsyncRead + Unpin> AsyncChaChaReader<R> {
+    /// Creates a new decrypting reader.
+    ///
+    /// # Arguments
+    /// * `inner` - Reader supplying the ciphertext.
+    /// * `cipher` - Cipher positioned where decryption should start.
+    pub fn new(inner: R, cipher: FastChaCha20) -> Self {
+        Self { inner, cipher }
+    }
+
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner reader.
+    ///
+    /// Reading from it directly desynchronizes the keystream.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns a reference to the cipher.
+    pub fn cipher(&self) -> &FastChaCha20 {
+        &self.cipher
+    }
+
+    /// Returns the inner reader and the cipher.
+    pub fn into_inner(self) -> (R, FastChaCha20) {
+        (self.inner, self.cipher)
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for AsyncChaChaReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let before = buf.filled().len();
+
+        let res = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = res {
+            this.cipher.apply_keystream(&mut buf.filled_mut()[before..]);
+        }
+        res
+    }
+}
@@ -0,0 +1,210 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Batch processing of many independent (nonce, counter, buffer) streams under one key.
+//!
+//! Creating a [`FastChaCha20`](crate::FastChaCha20) per packet repeats the key parsing and
+//! the CPU capability check for every packet. [`ChaChaBatch`] does that once per key and then
+//! processes a whole slice of [`BatchJob`]s:
+//!
+//! - when the assembly is available, every job goes straight to `ChaCha20_ctr32`: even for
+//!   a single block, one asm call per job beats a four-lane [`keystream4`] pass (compare
+//!   `chacha20/asm` and `chacha20/simd4` in `benches/`);
+//! - otherwise short jobs (up to [`SHORT_JOB_LEN`] bytes) are scheduled onto the four lanes
+//!   of [`keystream4`], so four unrelated streams advance in a single SIMD pass, and longer
+//!   jobs go to the Rust fallback behind `ChaCha20_ctr32` one at a time.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::batch::{BatchJob, ChaChaBatch};
+//!
+//! let batch = ChaChaBatch::new(&[7u8; 32]);
+//! let mut a = *b"first packet";
+//! let mut b = *b"second packet";
+//! let mut jobs = [
+//!     BatchJob { nonce: [1u8; 12], counter: 0, data: &mut a },
+//!     BatchJob { nonce: [2u8; 12], counter: 0, data: &mut b },
+//! ];
+//! batch.process(&mut jobs);
+//! ```
+
+use crate::fallback_chacha20::keystream4;
+use crate::{init_cpu_caps, is_asm_available, ChaCha20_ctr32};
+
+/// Without the assembly, jobs up to this length (in bytes) are interleaved in SIMD lanes.
+pub const SHORT_JOB_LEN: usize = 256;
+
+/// One independent stream in a batch.
+pub struct BatchJob<'a> {
+    /// 12-byte (96-bit) nonce of this stream.
+    pub nonce: [u8; 12],
+    /// Initial block counter of this stream.
+    pub counter: u32,
+    /// Buffer to encrypt/decrypt in-place.
+    pub data: &'a mut [u8],
+}
+
+/// A lane of the interleaved scheduler: which job it serves and how far it got.
+#[derive(Clone, Copy)]
+struct Lane {
+    job: usize,
+    counter: [u32; 4],
+    offset: usize,
+}
+
+/// Keyed context that encrypts batches of independent streams.
+#[derive(Clone)]
+pub struct ChaChaBatch {
+    /// 256-bit key as 8 u32 words.
+    key_words: [u32; 8],
+    /// Whether `ChaCha20_ctr32` is the assembly, which then takes every job.
+    asm: bool,
+}
+
+impl ChaChaBatch {
+    /// Creates a batch context for the given key.
+    ///
+    /// # Arguments
+    /// * `key` - 32-byte (256-bit) key shared by all jobs.
+    ///
+    /// # Panics
+    /// With the `self_test` feature, panics if a backend failed its self-test.
+    pub fn new(key: &[u8; 32]) -> Self {
+        init_cpu_caps();
+        #[cfg(feature = "self_test")]
+        crate::self_test::require();
+
+        let mut key_words = [0u32; 8];
+        for (word, chunk) in key_words.iter_mut().zip(key.chunks_exact(4)) {
+            *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        Self { key_words, asm: is_asm_available() }
+    }
+
+    /// Applies each job's keystream to its buffer in-place.
+    ///
+    /// The output of every job is identical to
+    /// `FastChaCha20::new_with_counter(key, job.nonce, job.counter).apply_keystream(job.data)`.
+    ///
+    /// # Arguments
+    /// * `jobs` - Streams to process; they may have different lengths, nonces and counters.
+    pub fn process(&self, jobs: &mut [BatchJob<'_>]) {
+        let mut lanes: [Option<Lane>; 4] = [None; 4];
+        let mut next = 0;
+
+        loop {
+            // Refill idle lanes, handing long jobs (or all of them, with the asm) directly to
+            // `ChaCha20_ctr32` on the way.
+            for lane in lanes.iter_mut().filter(|l| l.is_none()) {
+                while next < jobs.len() {
+                    let job = &mut jobs[next];
+                    next += 1;
+                    if job.data.is_empty() {
+                        continue;
+                    }
+                    let counter = job_counter(job);
+                    if self.asm || job.data.len() > SHORT_JOB_LEN {
+                        unsafe {
+                            ChaCha20_ctr32(
+                                job.data.as_mut_ptr(),
+                                job.data.as_mut_ptr(),
+                                job.data.len(),
+                                self.key_words.as_ptr(),
+                                counter.as_ptr(),
+                            );
+                        }
+                        continue;
+                    }
+                    *lane = Some(Lane { job: next - 1, counter, offset: 0 });
+                    break;
+                }
+            }
+
+            if lanes.iter().all(|l| l.is_none()) {
+                break;
+            }
+
+            let mut counters = [[0u32; 4]; 4];
+            for (c, lane) in counters.iter_mut().zip(lanes.iter()) {
+                if let Some(lane) = lane {
+                    *c = lane.counter;
+                }
+            }
+            let mut blocks = [[0u8; 64]; 4];
+            keystream4(&mut blocks, &self.key_words, &counters, 10);
+
+            for (slot, block) in lanes.iter_mut().zip(blocks.iter()) {
+                let Some(lane) = slot else { continue };
+                let data = &mut jobs[lane.job].data[lane.offset..];
+                let n = data.len().min(64);
+                for (d, k) in data[..n].iter_mut().zip(block.iter()) {
+                    *d ^= *k;
+                }
+                lane.offset += n;
+                lane.counter[0] = lane.counter[0].wrapping_add(1);
+                if lane.offset == jobs[lane.job].data.len() {
+                    *slot = None;
+                }
+            }
+        }
+    }
+}
+
+/// Builds the 4-word counter/nonce block of a job.
+#[inline(always)]
+fn job_counter(job: &BatchJob<'_>) -> [u32; 4] {
+    let n = &job.nonce;
+    [
+        job.counter,
+        u32::from_le_bytes([n[0], n[1], n[2], n[3]]),
+        u32::from_le_bytes([n[4], n[5], n[6], n[7]]),
+        u32::from_le_bytes([n[8], n[9], n[10], n[11]]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchJob, ChaChaBatch, SHORT_JOB_LEN};
+
+    /// The four-lane scheduler, used when the asm is missing, must match the per-job path.
+    #[test]
+    fn lanes_match_per_job_calls() {
+        let lens = [0usize, 1, 12, 63, 64, 65, 128, SHORT_JOB_LEN, SHORT_JOB_LEN + 1, 1420];
+        let plain: [[u8; 1420]; 23] =
+            core::array::from_fn(|i| core::array::from_fn(|j| (i * 7 + j * 13) as u8));
+
+        let mut outputs = [plain, plain];
+        for (out, asm) in outputs.iter_mut().zip([false, true]) {
+            let batch = ChaChaBatch { asm, ..ChaChaBatch::new(&[9u8; 32]) };
+            let mut jobs: [BatchJob<'_>; 23] = {
+                let mut bufs = out.iter_mut().enumerate();
+                core::array::from_fn(|_| {
+                    let (i, buf) = bufs.next().unwrap();
+                    BatchJob {
+                        nonce: [i as u8; 12],
+                        counter: i as u32 * 3,
+                        data: &mut buf[..lens[i % lens.len()]],
+                    }
+                })
+            };
+            batch.process(&mut jobs);
+        }
+        assert!(outputs[0] == outputs[1], "lane output differs from ChaCha20_ctr32");
+        assert!(outputs[0] != plain);
+    }
+}
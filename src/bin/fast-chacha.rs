@@ -0,0 +1,258 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! `fast-chacha`: encrypts, decrypts and inspects [`fast_chacha::container`] files.
+//!
+//! Built with the `cli` feature: `cargo install fast_chacha --features cli`.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use argon2::{Algorithm as Argon2Algorithm, Argon2, Params, Version};
+use fast_chacha::container::{self, Algorithm, Header, Kdf, KEY_BYTES, MAX_CHUNK_SIZE};
+
+const USAGE: &str = "\
+Usage:
+  fast-chacha encrypt [OPTIONS] (--key-file FILE | --password-file FILE) [INPUT [OUTPUT]]
+  fast-chacha decrypt (--key-file FILE | --password-file FILE) [INPUT [OUTPUT]]
+  fast-chacha inspect [INPUT]
+
+INPUT and OUTPUT default to standard input and output; `-` selects them explicitly.
+
+Key options:
+  --key-file FILE          Use the 32 raw bytes of FILE as the key
+  --password-file FILE     Derive the key from the first line of FILE with Argon2id
+
+Encryption options:
+  --xchacha                Use XChaCha20-Poly1305 instead of ChaCha20-Poly1305
+  --chunk-size BYTES       Plaintext bytes per record (default 65536)
+  --kdf-memory KIB         Argon2id memory cost (default 65536)
+  --kdf-iterations N       Argon2id iterations (default 3)
+  --kdf-parallelism N      Argon2id lanes (default 1)
+";
+
+/// Largest Argon2id memory cost accepted from a container header (4 GiB).
+const MAX_KDF_MEMORY: u32 = 4 * 1024 * 1024;
+
+/// Source of the container key.
+enum KeySource {
+    KeyFile(PathBuf),
+    PasswordFile(PathBuf),
+}
+
+/// Parsed command line.
+struct Options {
+    command: String,
+    key: Option<KeySource>,
+    xchacha: bool,
+    chunk_size: u32,
+    kdf: [u32; 3],
+    paths: Vec<String>,
+}
+
+/// Parses the arguments following the program name.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let command = args.next().ok_or("missing command")?;
+    let mut options = Options {
+        command,
+        key: None,
+        xchacha: false,
+        chunk_size: container::DEFAULT_CHUNK_SIZE,
+        kdf: [64 * 1024, 3, 1],
+        paths: Vec::new(),
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{name} needs a value"));
+        let number = |name: &str, v: String| {
+            v.parse::<u32>().map_err(|_| format!("{name}: invalid number `{v}`"))
+        };
+        match arg.as_str() {
+            "--key-file" => options.key = Some(KeySource::KeyFile(value(&arg)?.into())),
+            "--password-file" => options.key = Some(KeySource::PasswordFile(value(&arg)?.into())),
+            "--xchacha" => options.xchacha = true,
+            "--chunk-size" => options.chunk_size = number(&arg, value(&arg)?)?,
+            "--kdf-memory" => options.kdf[0] = number(&arg, value(&arg)?)?,
+            "--kdf-iterations" => options.kdf[1] = number(&arg, value(&arg)?)?,
+            "--kdf-parallelism" => options.kdf[2] = number(&arg, value(&arg)?)?,
+            "-h" | "--help" => return Err(String::new()),
+            _ if arg.starts_with("--") => return Err(format!("unknown option `{arg}`")),
+            _ => options.paths.push(arg),
+        }
+    }
+
+    let max_paths = if options.command == "inspect" { 1 } else { 2 };
+    if options.paths.len() > max_paths {
+        return Err("too many arguments".into());
+    }
+    if options.chunk_size == 0 || options.chunk_size > MAX_CHUNK_SIZE {
+        return Err(format!("--chunk-size must be between 1 and {MAX_CHUNK_SIZE}"));
+    }
+    Ok(options)
+}
+
+/// Opens INPUT, or standard input for `-` or no path.
+fn open_input(path: Option<&String>) -> io::Result<Box<dyn Read>> {
+    Ok(match path.map(String::as_str) {
+        None | Some("-") => Box::new(io::stdin().lock()),
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+    })
+}
+
+/// Creates OUTPUT, or standard output for `-` or no path.
+fn open_output(path: Option<&String>) -> io::Result<Box<dyn Write>> {
+    Ok(match path.map(String::as_str) {
+        None | Some("-") => Box::new(io::stdout().lock()),
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+    })
+}
+
+/// Returns the bytes of a password file without the trailing line break.
+fn read_password(path: &PathBuf) -> Result<Vec<u8>, String> {
+    let mut password = fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    if password.ends_with(b"\n") {
+        password.pop();
+        if password.ends_with(b"\r") {
+            password.pop();
+        }
+    }
+    Ok(password)
+}
+
+/// Obtains the container key described by `header`.
+fn load_key(source: &KeySource, header: &Header) -> Result<[u8; KEY_BYTES], String> {
+    match (source, header.kdf) {
+        (KeySource::KeyFile(path), Kdf::None) => {
+            let key = fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+            key.try_into().map_err(|_| format!("{}: key must be exactly 32 bytes", path.display()))
+        }
+        (KeySource::PasswordFile(path), Kdf::Argon2id { m_cost, t_cost, p_cost }) => {
+            if m_cost > MAX_KDF_MEMORY {
+                return Err(format!("Argon2id memory cost {m_cost} KiB is too large"));
+            }
+            let params = Params::new(m_cost, t_cost, p_cost, Some(KEY_BYTES))
+                .map_err(|e| format!("invalid Argon2id parameters: {e}"))?;
+            let mut key = [0u8; KEY_BYTES];
+            Argon2::new(Argon2Algorithm::Argon2id, Version::V0x13, params)
+                .hash_password_into(&read_password(path)?, &header.salt, &mut key)
+                .map_err(|e| format!("Argon2id: {e}"))?;
+            Ok(key)
+        }
+        (KeySource::KeyFile(_), _) => Err("file was encrypted with a password".into()),
+        (KeySource::PasswordFile(_), _) => Err("file was encrypted with a raw key".into()),
+    }
+}
+
+fn encrypt(options: &Options) -> Result<(), String> {
+    let source = options.key.as_ref().ok_or("--key-file or --password-file is required")?;
+    let algorithm =
+        if options.xchacha { Algorithm::XChaCha20Poly1305 } else { Algorithm::ChaCha20Poly1305 };
+    let kdf = match source {
+        KeySource::KeyFile(_) => Kdf::None,
+        KeySource::PasswordFile(_) => {
+            let [m_cost, t_cost, p_cost] = options.kdf;
+            Kdf::Argon2id { m_cost, t_cost, p_cost }
+        }
+    };
+
+    let mut salt = [0u8; container::SALT_BYTES];
+    let mut nonce_prefix = [0u8; container::NONCE_PREFIX_BYTES];
+    if let Kdf::Argon2id { .. } = kdf {
+        getrandom::fill(&mut salt).map_err(|e| format!("getrandom: {e}"))?;
+    }
+    getrandom::fill(&mut nonce_prefix).map_err(|e| format!("getrandom: {e}"))?;
+    let mut header = Header::new(algorithm, kdf, salt, nonce_prefix);
+    header.chunk_size = options.chunk_size;
+    let key = load_key(source, &header)?;
+
+    let input = open_input(options.paths.first()).map_err(|e| e.to_string())?;
+    let mut output = open_output(options.paths.get(1)).map_err(|e| e.to_string())?;
+    container::encrypt(&key, &header, input, &mut output).map_err(|e| e.to_string())?;
+    output.flush().map_err(|e| e.to_string())
+}
+
+fn decrypt(options: &Options) -> Result<(), String> {
+    let source = options.key.as_ref().ok_or("--key-file or --password-file is required")?;
+    let mut input = open_input(options.paths.first()).map_err(|e| e.to_string())?;
+    let header = Header::read_from(&mut input).map_err(|e| e.to_string())?;
+    let key = load_key(source, &header)?;
+
+    let mut output = open_output(options.paths.get(1)).map_err(|e| e.to_string())?;
+    let result = container::decrypt(&key, &header, input, &mut output).and_then(|_| output.flush());
+    if let Err(e) = result {
+        // Never leave unauthenticated or partial plaintext behind.
+        drop(output);
+        if let Some(path) = options.paths.get(1).filter(|p| *p != "-") {
+            let _ = fs::remove_file(path);
+        }
+        return Err(e.to_string());
+    }
+    Ok(())
+}
+
+fn inspect(options: &Options) -> Result<(), String> {
+    let input = open_input(options.paths.first()).map_err(|e| e.to_string())?;
+    let header = Header::read_from(input).map_err(|e| e.to_string())?;
+
+    println!("format:     fast_chacha container v{}", container::VERSION);
+    println!("algorithm:  {}", header.algorithm.name());
+    println!("chunk size: {} bytes", header.chunk_size);
+    match header.kdf {
+        Kdf::None => println!("kdf:        none (raw key)"),
+        Kdf::Argon2id { m_cost, t_cost, p_cost } => {
+            println!("kdf:        Argon2id, m={m_cost} KiB, t={t_cost}, p={p_cost}");
+            let salt: String = header.salt.iter().map(|b| format!("{b:02x}")).collect();
+            println!("salt:       {salt}");
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        // `--help` is reported as an empty error.
+        Err(e) if e.is_empty() => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("fast-chacha: {e}");
+            eprint!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = match options.command.as_str() {
+        "encrypt" => encrypt(&options),
+        "decrypt" => decrypt(&options),
+        "inspect" => inspect(&options),
+        other => {
+            eprintln!("fast-chacha: unknown command `{other}`");
+            eprint!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("fast-chacha: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
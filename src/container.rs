@@ -0,0 +1,403 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Self-describing encrypted file container, as written by the `fast-chacha` binary.
+//!
+//! A container is a 64-byte [`Header`] followed by authenticated records:
+//!
+//! | Offset | Size | Field |
+//! |--------|------|-------|
+//! | 0      | 4    | magic `FCHA` |
+//! | 4      | 1    | format version (1) |
+//! | 5      | 1    | [`Algorithm`] |
+//! | 6      | 1    | [`Kdf`] identifier (0: raw key, 1: Argon2id) |
+//! | 7      | 1    | reserved, 0 |
+//! | 8      | 4    | chunk size (LE32) |
+//! | 12     | 12   | KDF memory (KiB), iterations, parallelism (LE32 each; 0 for a raw key) |
+//! | 24     | 16   | KDF salt |
+//! | 40     | 19   | nonce prefix |
+//! | 59     | 5    | reserved, 0 |
+//!
+//! The plaintext is cut into chunks of `chunk size` bytes, and each chunk is sealed into a
+//! record of `chunk + 16` bytes. The last record always holds less than a full chunk (it
+//! is empty when the plaintext length is a multiple of the chunk size), which is how
+//! readers recognize it. Record `i` uses the nonce `prefix || i.to_be_bytes() || last`
+//! (the STREAM construction), where `prefix` is the first 7 bytes of the nonce prefix field
+//! for ChaCha20-Poly1305 and the first 19 for XChaCha20-Poly1305, and `last` is 1 for the
+//! last record and 0 otherwise. The whole header is the AAD of every record, so records
+//! cannot be reordered, dropped, truncated or moved to another container, and the header
+//! cannot be altered without detection.
+//!
+//! Key derivation is left to the caller: the header only records its parameters.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::container::{decrypt, encrypt, Algorithm, Header, Kdf};
+//!
+//! let key = [3u8; 32];
+//! let header = Header::new(Algorithm::XChaCha20Poly1305, Kdf::None, [0u8; 16], [9u8; 19]);
+//!
+//! let mut file = Vec::new();
+//! encrypt(&key, &header, &b"file contents"[..], &mut file).unwrap();
+//!
+//! let mut input = &file[..];
+//! let header = Header::read_from(&mut input).unwrap();
+//! let mut plaintext = Vec::new();
+//! decrypt(&key, &header, input, &mut plaintext).unwrap();
+//! assert_eq!(plaintext, b"file contents");
+//! ```
+
+use std::io::{self, Read, Write};
+use std::vec;
+
+use crate::aead::{ChaCha20Poly1305, XChaCha20Poly1305};
+pub use crate::aead::{KEY_BYTES, TAG_BYTES};
+use crate::Error;
+
+/// Magic bytes at the start of every container.
+pub const MAGIC: [u8; 4] = *b"FCHA";
+/// Format version written by this implementation.
+pub const VERSION: u8 = 1;
+/// Size of the header, in bytes.
+pub const HEADER_BYTES: usize = 64;
+/// Size of the KDF salt, in bytes.
+pub const SALT_BYTES: usize = 16;
+/// Size of the nonce prefix field, in bytes.
+pub const NONCE_PREFIX_BYTES: usize = 19;
+/// Chunk size used by [`Header::new`], in bytes.
+pub const DEFAULT_CHUNK_SIZE: u32 = 64 * 1024;
+/// Largest chunk size readers accept, in bytes.
+pub const MAX_CHUNK_SIZE: u32 = 16 * 1024 * 1024;
+
+/// AEAD used for the records of a container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// ChaCha20-Poly1305 (RFC 8439) with 7 random nonce prefix bytes.
+    ChaCha20Poly1305 = 1,
+    /// XChaCha20-Poly1305 with 19 random nonce prefix bytes.
+    XChaCha20Poly1305 = 2,
+}
+
+impl Algorithm {
+    /// Returns the usual name of the algorithm.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::ChaCha20Poly1305 => "ChaCha20-Poly1305",
+            Algorithm::XChaCha20Poly1305 => "XChaCha20-Poly1305",
+        }
+    }
+
+    /// Number of nonce prefix bytes the algorithm uses.
+    fn prefix_len(&self) -> usize {
+        match self {
+            Algorithm::ChaCha20Poly1305 => 7,
+            Algorithm::XChaCha20Poly1305 => NONCE_PREFIX_BYTES,
+        }
+    }
+}
+
+/// How the container key was derived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kdf {
+    /// The key was supplied directly.
+    None,
+    /// The key was derived from a passphrase with Argon2id (version 0x13) and the salt.
+    Argon2id {
+        /// Memory cost, in KiB.
+        m_cost: u32,
+        /// Number of iterations.
+        t_cost: u32,
+        /// Degree of parallelism.
+        p_cost: u32,
+    },
+}
+
+/// Container header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// Record AEAD.
+    pub algorithm: Algorithm,
+    /// Key derivation parameters.
+    pub kdf: Kdf,
+    /// Plaintext bytes per record, between 1 and [`MAX_CHUNK_SIZE`].
+    pub chunk_size: u32,
+    /// KDF salt; unused (but still stored) with [`Kdf::None`].
+    pub salt: [u8; SALT_BYTES],
+    /// Random nonce prefix; ChaCha20-Poly1305 only uses the first 7 bytes.
+    pub nonce_prefix: [u8; NONCE_PREFIX_BYTES],
+}
+
+impl Header {
+    /// Creates a header with the [`DEFAULT_CHUNK_SIZE`].
+    ///
+    /// # Arguments
+    /// * `algorithm` - Record AEAD.
+    /// * `kdf` - Key derivation parameters to record.
+    /// * `salt` - KDF salt; should be random when a KDF is used.
+    /// * `nonce_prefix` - Random bytes; must never repeat for the same key.
+    pub fn new(
+        algorithm: Algorithm,
+        kdf: Kdf,
+        salt: [u8; SALT_BYTES],
+        nonce_prefix: [u8; NONCE_PREFIX_BYTES],
+    ) -> Self {
+        Self { algorithm, kdf, chunk_size: DEFAULT_CHUNK_SIZE, salt, nonce_prefix }
+    }
+
+    /// Serializes the header.
+    pub fn to_bytes(&self) -> [u8; HEADER_BYTES] {
+        let mut out = [0u8; HEADER_BYTES];
+        out[..4].copy_from_slice(&MAGIC);
+        out[4] = VERSION;
+        out[5] = self.algorithm as u8;
+        let (kdf, params) = match self.kdf {
+            Kdf::None => (0, [0; 3]),
+            Kdf::Argon2id { m_cost, t_cost, p_cost } => (1, [m_cost, t_cost, p_cost]),
+        };
+        out[6] = kdf;
+        out[8..12].copy_from_slice(&self.chunk_size.to_le_bytes());
+        for (o, p) in out[12..24].chunks_exact_mut(4).zip(params) {
+            o.copy_from_slice(&p.to_le_bytes());
+        }
+        out[24..40].copy_from_slice(&self.salt);
+        out[40..40 + NONCE_PREFIX_BYTES].copy_from_slice(&self.nonce_prefix);
+        out
+    }
+
+    /// Parses a header.
+    ///
+    /// # Errors
+    /// [`Error::InvalidHeader`] if the magic, version, algorithm, KDF or chunk size is not
+    /// recognized, or a reserved byte is not zero.
+    pub fn from_bytes(bytes: &[u8; HEADER_BYTES]) -> Result<Self, Error> {
+        let le32 = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+
+        if bytes[..4] != MAGIC || bytes[4] != VERSION || bytes[7] != 0 {
+            return Err(Error::InvalidHeader);
+        }
+        if bytes[40 + NONCE_PREFIX_BYTES..].iter().any(|&b| b != 0) {
+            return Err(Error::InvalidHeader);
+        }
+        let algorithm = match bytes[5] {
+            1 => Algorithm::ChaCha20Poly1305,
+            2 => Algorithm::XChaCha20Poly1305,
+            _ => return Err(Error::InvalidHeader),
+        };
+        let kdf = match bytes[6] {
+            0 if bytes[12..24].iter().all(|&b| b == 0) => Kdf::None,
+            1 => Kdf::Argon2id { m_cost: le32(12), t_cost: le32(16), p_cost: le32(20) },
+            _ => return Err(Error::InvalidHeader),
+        };
+        let chunk_size = le32(8);
+        if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+            return Err(Error::InvalidHeader);
+        }
+
+        Ok(Self {
+            algorithm,
+            kdf,
+            chunk_size,
+            salt: bytes[24..40].try_into().unwrap(),
+            nonce_prefix: bytes[40..40 + NONCE_PREFIX_BYTES].try_into().unwrap(),
+        })
+    }
+
+    /// Reads and parses the header at the start of `reader`.
+    ///
+    /// # Errors
+    /// I/O errors of `reader`, or [`io::ErrorKind::InvalidData`] carrying
+    /// [`Error::InvalidHeader`].
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut bytes = [0u8; HEADER_BYTES];
+        reader.read_exact(&mut bytes)?;
+        Ok(Self::from_bytes(&bytes)?)
+    }
+}
+
+/// Record AEAD keyed for one container.
+enum Sealer {
+    ChaCha(ChaCha20Poly1305),
+    XChaCha(XChaCha20Poly1305),
+}
+
+/// Record encryption state: key, header bytes and record index.
+struct Records {
+    sealer: Sealer,
+    header: [u8; HEADER_BYTES],
+    prefix: [u8; NONCE_PREFIX_BYTES],
+    prefix_len: usize,
+    index: u64,
+}
+
+impl Records {
+    fn new(key: &[u8; KEY_BYTES], header: &Header) -> Self {
+        let sealer = match header.algorithm {
+            Algorithm::ChaCha20Poly1305 => Sealer::ChaCha(ChaCha20Poly1305::new(key)),
+            Algorithm::XChaCha20Poly1305 => Sealer::XChaCha(XChaCha20Poly1305::new(key)),
+        };
+        Self {
+            sealer,
+            header: header.to_bytes(),
+            prefix: header.nonce_prefix,
+            prefix_len: header.algorithm.prefix_len(),
+            index: 0,
+        }
+    }
+
+    /// Builds the nonce of the current record; only the first `prefix_len + 5` bytes count.
+    fn nonce(&self, last: bool) -> Result<[u8; 24], Error> {
+        let index = u32::try_from(self.index).map_err(|_| Error::NonceExhausted)?;
+        let mut nonce = [0u8; 24];
+        nonce[..self.prefix_len].copy_from_slice(&self.prefix[..self.prefix_len]);
+        nonce[self.prefix_len..self.prefix_len + 4].copy_from_slice(&index.to_be_bytes());
+        nonce[self.prefix_len + 4] = last as u8;
+        Ok(nonce)
+    }
+
+    fn seal(&mut self, data: &mut [u8], last: bool) -> Result<[u8; TAG_BYTES], Error> {
+        let nonce = self.nonce(last)?;
+        let tag = match &self.sealer {
+            Sealer::ChaCha(aead) => {
+                aead.encrypt_in_place_detached(nonce[..12].try_into().unwrap(), &self.header, data)?
+            }
+            Sealer::XChaCha(aead) => aead.encrypt_in_place_detached(&nonce, &self.header, data)?,
+        };
+        self.index += 1;
+        Ok(tag)
+    }
+
+    fn open(&mut self, data: &mut [u8], tag: &[u8; TAG_BYTES], last: bool) -> Result<(), Error> {
+        let nonce = self.nonce(last)?;
+        match &self.sealer {
+            Sealer::ChaCha(aead) => aead.decrypt_in_place_detached(
+                nonce[..12].try_into().unwrap(),
+                &self.header,
+                data,
+                tag,
+            )?,
+            Sealer::XChaCha(aead) => {
+                aead.decrypt_in_place_detached(&nonce, &self.header, data, tag)?
+            }
+        }
+        self.index += 1;
+        Ok(())
+    }
+}
+
+/// Reads until `buf` is full or the reader reaches EOF, and returns the bytes read.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Writes `header` followed by the encrypted records of everything read from `reader`.
+///
+/// Returns the number of plaintext bytes encrypted.
+///
+/// # Arguments
+/// * `key` - 32-byte container key.
+/// * `header` - Header to write; its nonce prefix must never be reused with `key`.
+/// * `reader` - Plaintext source, read to EOF.
+/// * `writer` - Destination of the container.
+///
+/// # Errors
+/// I/O errors of `reader` or `writer`, [`Error::InvalidHeader`] if the chunk size is out of
+/// range, or [`Error::NonceExhausted`] if the plaintext needs more than 2^32 records.
+pub fn encrypt<R: Read, W: Write>(
+    key: &[u8; KEY_BYTES],
+    header: &Header,
+    mut reader: R,
+    mut writer: W,
+) -> io::Result<u64> {
+    if header.chunk_size == 0 || header.chunk_size > MAX_CHUNK_SIZE {
+        return Err(Error::InvalidHeader.into());
+    }
+    let chunk = header.chunk_size as usize;
+    let mut records = Records::new(key, header);
+    writer.write_all(&records.header)?;
+
+    let mut buf = vec![0u8; chunk + TAG_BYTES];
+    let mut total = 0u64;
+    loop {
+        let n = read_full(&mut reader, &mut buf[..chunk])?;
+        let last = n < chunk;
+        let tag = records.seal(&mut buf[..n], last)?;
+        buf[n..n + TAG_BYTES].copy_from_slice(&tag);
+        writer.write_all(&buf[..n + TAG_BYTES])?;
+        total += n as u64;
+        if last {
+            return Ok(total);
+        }
+    }
+}
+
+/// Decrypts the records following a header read with [`Header::read_from`].
+///
+/// Plaintext is written as soon as each record authenticates. If an error is returned,
+/// the output is incomplete and whatever was written must be discarded.
+///
+/// Returns the number of plaintext bytes written.
+///
+/// # Arguments
+/// * `key` - 32-byte container key.
+/// * `header` - The container header.
+/// * `reader` - Container contents after the header.
+/// * `writer` - Destination of the plaintext.
+///
+/// # Errors
+/// I/O errors of `reader` or `writer`, or [`io::ErrorKind::InvalidData`] carrying:
+/// - [`Error::AuthenticationFailed`] if a record or the header was forged or corrupted,
+///   the key is wrong, or data was appended after the last record.
+/// - [`Error::Truncated`] if the container ends before its last record.
+pub fn decrypt<R: Read, W: Write>(
+    key: &[u8; KEY_BYTES],
+    header: &Header,
+    mut reader: R,
+    mut writer: W,
+) -> io::Result<u64> {
+    if header.chunk_size == 0 || header.chunk_size > MAX_CHUNK_SIZE {
+        return Err(Error::InvalidHeader.into());
+    }
+    let record = header.chunk_size as usize + TAG_BYTES;
+    let mut records = Records::new(key, header);
+
+    let mut buf = vec![0u8; record];
+    let mut total = 0u64;
+    loop {
+        let n = read_full(&mut reader, &mut buf)?;
+        if n < TAG_BYTES {
+            return Err(Error::Truncated.into());
+        }
+        let last = n < record;
+        let (data, tag) = buf[..n].split_at_mut(n - TAG_BYTES);
+        records.open(data, (&*tag).try_into().unwrap(), last)?;
+        writer.write_all(data)?;
+        total += data.len() as u64;
+
+        if last {
+            return Ok(total);
+        }
+    }
+}
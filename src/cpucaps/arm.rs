@@ -32,7 +32,7 @@ use super::get_auxv;
 
 /// Global variable storing detected ARM CPU capabilities as bitflags.
 /// The value is set during initialization and used by cryptographic routines.
-#[no_mangle]
+#[export_name = "fast_chacha_OPENSSL_armcap_P"]
 pub static mut OPENSSL_armcap_P: u32 = 0;
 
 /// Atomic flag indicating whether ARM CPU capabilities have been initialized.
@@ -31,7 +31,7 @@ use super::get_auxv;
 
 /// Global variable storing detected MIPS CPU capabilities.
 /// The value is set during initialization and used by other modules.
-#[no_mangle]
+#[export_name = "fast_chacha_OPENSSL_mips_cap_P"]
 pub static mut OPENSSL_mips_cap_P: u32 = 0;
 
 /// Atomic flag indicating whether CPU capabilities have been initialized.
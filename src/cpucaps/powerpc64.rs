@@ -38,7 +38,7 @@ use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 /// # Safety
 /// This variable is mutable and not thread-safe by itself.
 /// Access must be synchronized or performed during initialization.
-#[no_mangle]
+#[export_name = "fast_chacha_OPENSSL_ppccap_P"]
 pub static mut OPENSSL_ppccap_P: u32 = 0;
 
 /// Atomic flag indicating whether CPU capability detection has been performed.
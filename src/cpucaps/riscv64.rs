@@ -33,8 +33,8 @@ use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 /// Global variable to store RISC-V CPU capabilities bitmask.
 ///
 /// This variable is intended to be accessed from C code as well,
-/// hence the `#[no_mangle]` attribute and `pub static mut`.
-#[no_mangle]
+/// hence the exported `fast_chacha_OPENSSL_riscvcap_P` symbol and `pub static mut`.
+#[export_name = "fast_chacha_OPENSSL_riscvcap_P"]
 pub static mut OPENSSL_riscvcap_P: u32 = 0;
 
 /// Atomic flag indicating whether CPU capabilities have been initialized.
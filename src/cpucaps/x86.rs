@@ -36,7 +36,7 @@ use core::arch::x86_64::{__cpuid, __cpuid_count};
 
 /// Exposed CPU capability flags, compatible with OpenSSL's ia32cap_P.
 /// This array is filled during initialization with detected CPU features.
-#[no_mangle]
+#[export_name = "fast_chacha_OPENSSL_ia32cap_P"]
 pub static mut OPENSSL_ia32cap_P: [u32; 5] = [0; 5];
 
 /// Atomic flag to ensure that CPU feature detection is performed only once.
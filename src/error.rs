@@ -0,0 +1,76 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Error type shared by the authenticated and protocol-level APIs of the crate.
+
+use core::fmt;
+
+/// Errors returned by the fallible APIs of this crate.
+///
+/// Authentication failures deliberately carry no detail about what went wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The authentication tag did not verify; the input was forged or corrupted.
+    AuthenticationFailed,
+    /// An input or output buffer has an invalid length.
+    InvalidLength,
+    /// The stream ended before its final chunk was seen.
+    Truncated,
+    /// Data was supplied after the final chunk of a stream.
+    StreamFinished,
+    /// The nonce or sequence number space is used up; the key must be replaced.
+    NonceExhausted,
+    /// A decrypted record carries no content type, only padding.
+    InvalidPadding,
+    /// A container or DTLS record header has an unknown or unsupported field.
+    InvalidHeader,
+    /// The operating system random number generator failed.
+    RandomUnavailable,
+    /// Persistent state could not be read or written.
+    Io,
+    /// A saved stream position is malformed or its nonce does not match the stream.
+    InvalidPosition,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Error::AuthenticationFailed => "authentication failed",
+            Error::InvalidLength => "invalid buffer length",
+            Error::Truncated => "stream truncated before the final chunk",
+            Error::StreamFinished => "data after the final chunk of the stream",
+            Error::NonceExhausted => "nonce space exhausted, rekey required",
+            Error::InvalidPadding => "record contains only padding",
+            Error::InvalidHeader => "unrecognized or unsupported header",
+            Error::RandomUnavailable => "random number generator unavailable",
+            Error::Io => "failed to access persistent state",
+            Error::InvalidPosition => "invalid or mismatched stream position",
+        };
+        f.write_str(msg)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    /// Wraps the error in an [`std::io::ErrorKind::InvalidData`] I/O error.
+    fn from(e: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
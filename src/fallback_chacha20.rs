@@ -42,6 +42,8 @@
 //!
 //! - `quarter_round`: Core ChaCha20 operation mixing four words of the state.
 //! - `xor`: Applies ChaCha20 keystream to a buffer for encryption/decryption or outputs keystream.
+//! - `hchacha`: Derives XChaCha subkeys from a key and a 128-bit nonce prefix.
+//! - `keystream4`: Generates four independent keystream blocks in interleaved lanes.
 //! - Tests: Verifies correct encryption/decryption round-trip.
 
 /// Performs a single ChaCha20 quarter round operation on the cipher state.
@@ -73,6 +75,58 @@ fn quarter_round(state: &mut [u32], a: usize, b: usize, c: usize, d: usize) {
     state[b] = state[b].rotate_left(7);
 }
 
+/// Applies the given number of ChaCha double rounds (column + diagonal) to `working`.
+#[inline(always)]
+fn double_rounds_apply(working: &mut [u32; 16], double_rounds: usize) {
+    for _ in 0..double_rounds {
+        // Column rounds
+        quarter_round(working, 0, 4, 8, 12);
+        quarter_round(working, 1, 5, 9, 13);
+        quarter_round(working, 2, 6, 10, 14);
+        quarter_round(working, 3, 7, 11, 15);
+        // Diagonal rounds
+        quarter_round(working, 0, 5, 10, 15);
+        quarter_round(working, 1, 6, 11, 12);
+        quarter_round(working, 2, 7, 8, 13);
+        quarter_round(working, 3, 4, 9, 14);
+    }
+}
+
+/// Computes the HChaCha20 function used to derive XChaCha20 subkeys.
+///
+/// HChaCha20 runs the ChaCha permutation over the key and a 128-bit input without the
+/// final feed-forward addition, and returns state words 0..4 and 12..16 as a new key.
+///
+/// # Arguments
+/// * `key` - Reference to an array of 8 u32 words (256-bit key).
+/// * `input` - First 16 bytes of the extended nonce, as 4 little-endian u32 words.
+/// * `double_rounds` - Number of double rounds (10 for XChaCha20, 6 for XChaCha12).
+///
+/// # Example
+/// ```rust
+/// use fast_chacha::fallback_chacha20::hchacha;
+/// let subkey = hchacha(&[0u32; 8], &[0u32; 4], 10);
+/// ```
+pub fn hchacha(key: &[u32; 8], input: &[u32; 4], double_rounds: usize) -> [u32; 8] {
+    let mut working = [
+        0x61707865, 0x3320646e, 0x79622d32, 0x6b206574, // constants
+        key[0], key[1], key[2], key[3], key[4], key[5], key[6], key[7], // key
+        input[0], input[1], input[2], input[3], // nonce prefix
+    ];
+    double_rounds_apply(&mut working, double_rounds);
+
+    [
+        working[0],
+        working[1],
+        working[2],
+        working[3],
+        working[12],
+        working[13],
+        working[14],
+        working[15],
+    ]
+}
+
 /// XORs the given data in-place with a ChaCha20 keystream generated from the provided key and counter.
 ///
 /// This function generates a single ChaCha20 block (64 bytes) using the provided key and counter,
@@ -118,20 +172,7 @@ pub fn xor(
     ];
 
     let mut working = state;
-
-    // Apply the specified number of double rounds (each double round = 2 rounds)
-    for _ in 0..double_rounds {
-        // Column rounds
-        quarter_round(&mut working, 0, 4, 8, 12);
-        quarter_round(&mut working, 1, 5, 9, 13);
-        quarter_round(&mut working, 2, 6, 10, 14);
-        quarter_round(&mut working, 3, 7, 11, 15);
-        // Diagonal rounds
-        quarter_round(&mut working, 0, 5, 10, 15);
-        quarter_round(&mut working, 1, 6, 11, 12);
-        quarter_round(&mut working, 2, 7, 8, 13);
-        quarter_round(&mut working, 3, 4, 9, 14);
-    }
+    double_rounds_apply(&mut working, double_rounds);
 
     // Add the original state to the working state, serialize, and XOR with data in one pass
     if keystream_only {
@@ -155,6 +196,105 @@ pub fn xor(
     }
 }
 
+/// Lane-wise wrapping addition of four independent state words.
+#[inline(always)]
+fn add4(a: [u32; 4], b: [u32; 4]) -> [u32; 4] {
+    [
+        a[0].wrapping_add(b[0]),
+        a[1].wrapping_add(b[1]),
+        a[2].wrapping_add(b[2]),
+        a[3].wrapping_add(b[3]),
+    ]
+}
+
+/// Lane-wise XOR followed by a left rotation of four independent state words.
+#[inline(always)]
+fn xor_rotl4(a: [u32; 4], b: [u32; 4], n: u32) -> [u32; 4] {
+    [
+        (a[0] ^ b[0]).rotate_left(n),
+        (a[1] ^ b[1]).rotate_left(n),
+        (a[2] ^ b[2]).rotate_left(n),
+        (a[3] ^ b[3]).rotate_left(n),
+    ]
+}
+
+/// Performs a ChaCha20 quarter round on four interleaved states at once.
+///
+/// Each state word is stored as `[u32; 4]`, one element per lane, so the compiler can map
+/// every operation to a single SIMD instruction.
+#[inline(always)]
+fn quarter_round4(state: &mut [[u32; 4]; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = add4(state[a], state[b]);
+    state[d] = xor_rotl4(state[d], state[a], 16);
+    state[c] = add4(state[c], state[d]);
+    state[b] = xor_rotl4(state[b], state[c], 12);
+    state[a] = add4(state[a], state[b]);
+    state[d] = xor_rotl4(state[d], state[a], 8);
+    state[c] = add4(state[c], state[d]);
+    state[b] = xor_rotl4(state[b], state[c], 7);
+}
+
+/// Generates four independent ChaCha20 keystream blocks in interleaved lanes.
+///
+/// Unlike the multi-block assembly paths, which compute consecutive blocks of one stream,
+/// every lane here has its own counter and nonce, so four unrelated short streams can be
+/// processed in a single pass.
+///
+/// # Arguments
+/// * `out` - Receives one 64-byte keystream block per lane.
+/// * `key` - Reference to an array of 8 u32 words (256-bit key), shared by all lanes.
+/// * `counters` - Block counter and nonce words for each lane.
+/// * `double_rounds` - Number of double rounds (standard is 10).
+///
+/// # Example
+/// ```rust
+/// use fast_chacha::fallback_chacha20::keystream4;
+/// let key = [0u32; 8];
+/// let counters = [[0u32, 0, 0, 0], [1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 0, 1]];
+/// let mut blocks = [[0u8; 64]; 4];
+/// keystream4(&mut blocks, &key, &counters, 10);
+/// ```
+pub fn keystream4(
+    out: &mut [[u8; 64]; 4],
+    key: &[u32; 8],
+    counters: &[[u32; 4]; 4],
+    double_rounds: usize,
+) {
+    let mut state = [[0u32; 4]; 16];
+    state[0] = [0x61707865; 4];
+    state[1] = [0x3320646e; 4];
+    state[2] = [0x79622d32; 4];
+    state[3] = [0x6b206574; 4];
+    for (i, word) in key.iter().enumerate() {
+        state[4 + i] = [*word; 4];
+    }
+    for i in 0..4 {
+        state[12 + i] = [counters[0][i], counters[1][i], counters[2][i], counters[3][i]];
+    }
+
+    let mut working = state;
+    for _ in 0..double_rounds {
+        // Column rounds
+        quarter_round4(&mut working, 0, 4, 8, 12);
+        quarter_round4(&mut working, 1, 5, 9, 13);
+        quarter_round4(&mut working, 2, 6, 10, 14);
+        quarter_round4(&mut working, 3, 7, 11, 15);
+        // Diagonal rounds
+        quarter_round4(&mut working, 0, 5, 10, 15);
+        quarter_round4(&mut working, 1, 6, 11, 12);
+        quarter_round4(&mut working, 2, 7, 8, 13);
+        quarter_round4(&mut working, 3, 4, 9, 14);
+    }
+
+    // Add the original state and serialize each lane into its own block
+    for (w, (work, init)) in working.iter().zip(state.iter()).enumerate() {
+        let sum = add4(*work, *init);
+        for lane in 0..4 {
+            out[lane][w * 4..w * 4 + 4].copy_from_slice(&sum[lane].to_le_bytes());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::fallback_chacha20::xor;
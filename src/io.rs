@@ -0,0 +1,368 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Streaming `std::io` adapters around [`FastChaCha20`].
+//!
+//! [`ChaChaWriter`] encrypts everything written to it before passing it to the inner writer,
+//! and [`ChaChaReader`] decrypts everything read from the inner reader. Both keep an internal
+//! buffer of [`BUFFER_SIZE`] bytes so that the keystream is applied in large multi-block runs,
+//! which is where the assembly implementations are fastest.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::io::{ChaChaReader, ChaChaWriter};
+//! use fast_chacha::FastChaCha20;
+//! use std::io::{Read, Write};
+//!
+//! let key = [7u8; 32];
+//! let nonce = [9u8; 12];
+//!
+//! let mut writer = ChaChaWriter::new(Vec::new(), FastChaCha20::new(&key, &nonce));
+//! writer.write_all(b"streamed plaintext").unwrap();
+//! let ciphertext = writer.into_inner().unwrap();
+//!
+//! let mut reader = ChaChaReader::new(&ciphertext[..], FastChaCha20::new(&key, &nonce));
+//! let mut plaintext = Vec::new();
+//! reader.read_to_end(&mut plaintext).unwrap();
+//! assert_eq!(plaintext, b"streamed plaintext");
+//! ```
+
+use std::boxed::Box;
+use std::io::{self, IoSliceMut, Read, Seek, SeekFrom, Write};
+use std::vec;
+
+use crate::FastChaCha20;
+
+/// First keystream position, in bytes, that the 32-bit block counter cannot reach.
+const MAX_STREAM_POS: u64 = (1 << 32) * 64;
+
+/// Size of the internal buffers, in bytes.
+///
+/// A multiple of 512 bytes, so full buffers always take the widest (8x/16x block) asm paths.
+pub const BUFFER_SIZE: usize = 16 * 1024;
+
+impl FastChaCha20 {
+    /// Applies the keystream to a list of non-contiguous buffers as if they were one buffer.
+    ///
+    /// The keystream carries across buffer boundaries: a block split between two buffers is
+    /// generated once and shared, and the block-aligned part of every buffer is handed to
+    /// `ChaCha20_ctr32` in a single call.
+    ///
+    /// # Arguments
+    /// * `bufs` - Buffers to encrypt/decrypt in-place, in stream order.
+    ///
+    /// # Example
+    /// ```
+    /// use fast_chacha::FastChaCha20;
+    /// use std::io::IoSliceMut;
+    ///
+    /// let mut cipher = FastChaCha20::new(&[0u8; 32], &[0u8; 12]);
+    /// let mut header = [0u8; 20];
+    /// let mut payload = [0u8; 1400];
+    /// let mut trailer = [0u8; 16];
+    /// cipher.apply_keystream_vectored(&mut [
+    ///     IoSliceMut::new(&mut header),
+    ///     IoSliceMut::new(&mut payload),
+    ///     IoSliceMut::new(&mut trailer),
+    /// ]);
+    /// ```
+    pub fn apply_keystream_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) {
+        // Keystream of the block at `self.counter`, valid while that block is partly consumed.
+        let mut carry = [0u8; 64];
+        let mut carry_valid = false;
+
+        for buf in bufs.iter_mut() {
+            let mut data: &mut [u8] = buf;
+
+            if self.offset != 0 && !data.is_empty() {
+                if !carry_valid {
+                    carry = [0u8; 64];
+                    self.xor_keystream(&mut carry, self.counter, None);
+                }
+                let n = (64 - self.offset).min(data.len());
+                for (d, k) in data[..n].iter_mut().zip(&carry[self.offset..]) {
+                    *d ^= *k;
+                }
+                self.advance(n);
+                carry_valid = self.offset != 0;
+                data = &mut data[n..];
+            }
+
+            if data.is_empty() {
+                continue;
+            }
+
+            let full = data.len() - data.len() % 64;
+            let (blocks, tail) = data.split_at_mut(full);
+            if !blocks.is_empty() {
+                self.xor_keystream(blocks, self.counter, None);
+                self.advance(full);
+            }
+            if !tail.is_empty() {
+                carry = [0u8; 64];
+                self.xor_keystream(&mut carry, self.counter, None);
+                for (d, k) in tail.iter_mut().zip(carry.iter()) {
+                    *d ^= *k;
+                }
+                self.advance(tail.len());
+                carry_valid = true;
+            }
+        }
+    }
+}
+
+/// Encrypts all data written to it and forwards the ciphertext to an inner writer.
+///
+/// Plaintext accepted by [`Write::write`] is collected in a buffer of [`BUFFER_SIZE`] bytes
+/// and encrypted with a single keystream call when the buffer is passed on to the inner
+/// writer, so many small writes still take the multi-block asm paths. Call [`Write::flush`]
+/// or [`ChaChaWriter::into_inner`] to make sure all buffered data reaches the inner writer;
+/// dropping the writer flushes on a best-effort basis and ignores errors.
+pub struct ChaChaWriter<W: Write> {
+    inner: Option<W>,
+    cipher: FastChaCha20,
+    buf: Box<[u8]>,
+    /// Number of bytes waiting in `buf`.
+    len: usize,
+    /// Number of bytes at the start of `buf` that are already encrypted.
+    encrypted: usize,
+}
+
+impl<W: Write> ChaChaWriter<W> {
+    /// Creates a new encrypting writer.
+    ///
+    /// # Arguments
+    /// * `inner` - Writer receiving the ciphertext.
+    /// * `cipher` - Cipher positioned where encryption should start.
+    pub fn new(inner: W, cipher: FastChaCha20) -> Self {
+        Self {
+            inner: Some(inner),
+            cipher,
+            buf: vec![0u8; BUFFER_SIZE].into_boxed_slice(),
+            len: 0,
+            encrypted: 0,
+        }
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().unwrap()
+    }
+
+    /// Returns a mutable reference to the inner writer.
+    ///
+    /// Writing to it directly bypasses encryption and the internal buffer.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner.as_mut().unwrap()
+    }
+
+    /// Returns a reference to the cipher.
+    ///
+    /// Its position only covers data that has been encrypted out of the internal buffer.
+    pub fn cipher(&self) -> &FastChaCha20 {
+        &self.cipher
+    }
+
+    /// Flushes the buffered data and returns the inner writer.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.flush_buf()?;
+        Ok(self.inner.take().unwrap())
+    }
+
+    /// Encrypts the buffered plaintext and writes all buffered ciphertext to the inner writer.
+    fn flush_buf(&mut self) -> io::Result<()> {
+        self.cipher.apply_keystream(&mut self.buf[self.encrypted..self.len]);
+        self.encrypted = self.len;
+
+        let inner = self.inner.as_mut().unwrap();
+        let mut written = 0;
+        let mut ret = Ok(());
+        while written < self.len {
+            match inner.write(&self.buf[written..self.len]) {
+                Ok(0) => {
+                    ret = Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write the buffered data",
+                    ));
+                    break;
+                }
+                Ok(n) => written += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    ret = Err(e);
+                    break;
+                }
+            }
+        }
+        // Keep whatever was not written, it is already encrypted.
+        self.buf.copy_within(written..self.len, 0);
+        self.len -= written;
+        self.encrypted = self.len;
+        ret
+    }
+}
+
+impl<W: Write> Write for ChaChaWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if data.is_empty() {
+            return Ok(0);
+        }
+        if self.len == self.buf.len() {
+            self.flush_buf()?;
+        }
+
+        // Plaintext is only encrypted in `flush_buf`, in one run over everything pending.
+        let n = data.len().min(self.buf.len() - self.len);
+        self.buf[self.len..self.len + n].copy_from_slice(&data[..n]);
+        self.len += n;
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flush_buf()?;
+        self.inner.as_mut().unwrap().flush()
+    }
+}
+
+impl<W: Write> Drop for ChaChaWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.flush_buf();
+        }
+    }
+}
+
+/// Decrypts all data read from an inner reader.
+///
+/// Ciphertext is read from the inner reader in chunks of up to [`BUFFER_SIZE`] bytes.
+/// Reads larger than the buffer bypass it and are decrypted directly in the caller's slice.
+///
+/// If the inner reader implements [`Seek`], so does `ChaChaReader`: position 0 of the inner
+/// stream corresponds to the cipher position at the time the reader was created, and the
+/// cipher is repositioned with [`FastChaCha20::seek`] on every seek. Seeking to a position
+/// the 32-bit block counter cannot reach (256 GiB from the start of the keystream) fails
+/// with [`io::ErrorKind::InvalidInput`] and leaves the reader where it was.
+pub struct ChaChaReader<R: Read> {
+    inner: R,
+    cipher: FastChaCha20,
+    /// Cipher position corresponding to offset 0 of the inner stream.
+    base: u64,
+    buf: Box<[u8]>,
+    /// Start of the decrypted bytes not yet returned to the caller.
+    pos: usize,
+    /// End of the decrypted bytes in `buf`.
+    filled: usize,
+}
+
+impl<R: Read> ChaChaReader<R> {
+    /// Creates a new decrypting reader.
+    ///
+    /// # Arguments
+    /// * `inner` - Reader supplying the ciphertext.
+    /// * `cipher` - Cipher positioned where decryption should start.
+    pub fn new(inner: R, cipher: FastChaCha20) -> Self {
+        let base = cipher.current_pos();
+        Self {
+            inner,
+            cipher,
+            base,
+            buf: vec![0u8; BUFFER_SIZE].into_boxed_slice(),
+            pos: 0,
+            filled: 0,
+        }
+    }
+
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner reader.
+    ///
+    /// Reading from it directly desynchronizes the keystream.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns a reference to the cipher.
+    pub fn cipher(&self) -> &FastChaCha20 {
+        &self.cipher
+    }
+
+    /// Returns the inner reader, discarding any buffered plaintext.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for ChaChaReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.filled {
+            // Large reads skip the buffer entirely.
+            if out.len() >= self.buf.len() {
+                let n = self.inner.read(out)?;
+                self.cipher.apply_keystream(&mut out[..n]);
+                return Ok(n);
+            }
+
+            let n = self.inner.read(&mut self.buf)?;
+            self.cipher.apply_keystream(&mut self.buf[..n]);
+            self.pos = 0;
+            self.filled = n;
+        }
+
+        let n = out.len().min(self.filled - self.pos);
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+
+        Ok(n)
+    }
+}
+
+impl<R: Read + Seek> Seek for ChaChaReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidInput, "seek past the keystream");
+        // Where the inner reader is now: the cipher has consumed exactly what it returned.
+        let inner_pos = self.cipher.current_pos() - self.base;
+
+        let pos = match pos {
+            // The inner reader is ahead of the caller by the unread buffered bytes.
+            SeekFrom::Current(n) => {
+                let remaining = (self.filled - self.pos) as i64;
+                let n = n.checked_sub(remaining).ok_or_else(invalid)?;
+                self.inner.seek(SeekFrom::Current(n))?
+            }
+            other => self.inner.seek(other)?,
+        };
+
+        // The 32-bit block counter covers 2^32 blocks from the start of the keystream.
+        let target = match self.base.checked_add(pos) {
+            Some(target) if target < MAX_STREAM_POS => target,
+            _ => {
+                self.inner.seek(SeekFrom::Start(inner_pos))?;
+                return Err(invalid());
+            }
+        };
+
+        self.pos = 0;
+        self.filled = 0;
+        self.cipher.seek(target);
+
+        Ok(pos)
+    }
+}
@@ -64,13 +64,49 @@ use core::{
     sync::atomic::{AtomicBool, Ordering},
 };
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "adiantum")]
+pub mod adiantum;
+pub mod aead;
+#[cfg(feature = "tokio")]
+pub mod async_io;
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod container;
+mod error;
 pub mod fallback_chacha20;
+#[cfg(feature = "std")]
+pub mod io;
+pub mod noise;
+pub mod nonce;
+#[cfg(feature = "rayon")]
+mod parallel;
+pub mod poly1305;
+pub mod position;
+pub mod quic;
+pub mod secretstream;
+pub mod sector;
+#[cfg(feature = "self_test")]
+pub mod self_test;
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod simd128;
+pub mod ssh;
+pub mod tls;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod wireguard;
+
+pub use error::Error;
 
 /// Atomic flag indicating whether the fallback (pure Rust) implementation was triggered.
 static FALLBACK_TRIGGERED: AtomicBool = AtomicBool::new(false);
 
 /// Fallback ChaCha20 implementation using pure Rust.
 ///
+/// On `wasm32` with `simd128` enabled, runs of four whole blocks use the SIMD backend.
+///
 /// # Arguments
 /// * `out` - Output buffer for the keystream XOR result.
 /// * `inp` - Input buffer to be encrypted/decrypted.
@@ -86,6 +122,13 @@ fn fallback(
     double_rounds: usize,
 ) {
     let mut offset = 0;
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    while len - offset >= simd128::CHUNK_BYTES {
+        let chunk = &mut out[offset..offset + simd128::CHUNK_BYTES];
+        simd128::xor4(chunk, keystream_only, key, counter, double_rounds);
+        counter[0] = counter[0].wrapping_add(4);
+        offset += simd128::CHUNK_BYTES;
+    }
     while offset < len {
         let block_len = (len - offset).min(64);
         fallback_chacha20::xor(
@@ -102,16 +145,19 @@ fn fallback(
 
 /// C-compatible ChaCha20 function, used as a fallback or as the main implementation if assembly is not available.
 ///
+/// Exported to C as `fast_chacha_ChaCha20_ctr32_c`.
+///
 /// # Safety
 /// This function is unsafe because it operates on raw pointers.
 ///
 /// # Arguments
 /// * `out` - Pointer to output buffer.
-/// * `inp` - Pointer to input buffer.
+/// * `inp` - Pointer to input buffer; may equal `out`, or be NULL to output raw keystream.
 /// * `len` - Length of the input/output buffers.
 /// * `key` - Pointer to the key (8 u32 words).
 /// * `counter` - Pointer to the counter (4 u32 words).
-#[no_mangle]
+#[export_name = "fast_chacha_ChaCha20_ctr32_c"]
+#[allow(non_snake_case)]
 pub unsafe extern "C" fn ChaCha20_ctr32_c(
     out: *mut u8,
     inp: *const u8,
@@ -121,8 +167,11 @@ pub unsafe extern "C" fn ChaCha20_ctr32_c(
 ) {
     FALLBACK_TRIGGERED.store(true, Ordering::SeqCst);
 
-    let out = slice::from_raw_parts_mut(out, len);
     let keystream_only = inp.is_null();
+    if !keystream_only && !ptr::eq(inp, out) {
+        ptr::copy(inp, out, len);
+    }
+    let out = slice::from_raw_parts_mut(out, len);
     let key = &*(key as *const [u32; 8]);
     let ctr = &mut *(counter as *mut [u32; 4]);
 
@@ -131,7 +180,9 @@ pub unsafe extern "C" fn ChaCha20_ctr32_c(
 
 #[cfg(fast_chacha_asm)]
 extern "C" {
-    /// External assembly-optimized ChaCha20 function.
+    /// External assembly-optimized ChaCha20 function, prefixed by `build.rs` so it cannot
+    /// clash with OpenSSL's own `ChaCha20_ctr32`.
+    #[link_name = "fast_chacha_ChaCha20_ctr32"]
     fn ChaCha20_ctr32(
         out: *mut u8,
         inp: *const u8,
@@ -144,9 +195,12 @@ extern "C" {
 #[cfg(not(fast_chacha_asm))]
 /// Pure Rust fallback for ChaCha20_ctr32 if assembly is not enabled.
 ///
+/// Exported to C as `fast_chacha_ChaCha20_ctr32`, like the assembly version.
+///
 /// # Safety
 /// This function is unsafe because it operates on raw pointers.
-#[no_mangle]
+#[export_name = "fast_chacha_ChaCha20_ctr32"]
+#[allow(non_snake_case)]
 pub unsafe extern "C" fn ChaCha20_ctr32(
     out: *mut u8,
     inp: *const u8,
@@ -169,15 +223,17 @@ fn init_cpu_caps() {
 
 /// FastChaCha20: Main struct representing a ChaCha20 cipher instance.
 ///
-/// Holds the key and counter state for encryption/decryption.
-///
-///
+/// Holds the key and counter state for encryption/decryption. The stream position is
+/// tracked with byte granularity, so consecutive calls to [`FastChaCha20::apply_keystream`]
+/// continue the keystream exactly where the previous call stopped.
 #[derive(Clone)]
 pub struct FastChaCha20 {
     /// 256-bit key as 8 u32 words.
     key_words: [u32; 8],
     /// 128-bit counter as 4 u32 words.
     counter: [u32; 4],
+    /// Byte offset inside the block addressed by `counter[0]` (always below 64).
+    offset: usize,
 }
 
 impl FastChaCha20 {
@@ -188,7 +244,8 @@ impl FastChaCha20 {
     /// * `nonce` - 12-byte (96-bit) nonce.
     ///
     /// # Panics
-    /// Panics if the key or nonce are not the correct length.
+    /// Panics if the key or nonce are not the correct length, or, with the `self_test`
+    /// feature, if a backend failed its self-test.
     ///
     /// # Example
     /// ```
@@ -203,6 +260,8 @@ impl FastChaCha20 {
         debug_assert!(nonce.len() == 12, "Nonce must be 12 bytes");
 
         init_cpu_caps();
+        #[cfg(feature = "self_test")]
+        self_test::require();
 
         let key_words = [
             u32::from_le_bytes([key[0], key[1], key[2], key[3]]),
@@ -222,7 +281,127 @@ impl FastChaCha20 {
             u32::from_le_bytes([nonce[8], nonce[9], nonce[10], nonce[11]]),
         ];
 
-        Self { key_words, counter }
+        Self { key_words, counter, offset: 0 }
+    }
+
+    /// Creates a new XChaCha20 instance with the given key and 24-byte extended nonce.
+    ///
+    /// The first 16 bytes of the nonce are used with HChaCha20 to derive a subkey; the
+    /// remaining 8 bytes form the last two words of the regular 96-bit nonce, whose first
+    /// word is zero (draft-irtf-cfrg-xchacha, libsodium-compatible).
+    ///
+    /// # Arguments
+    /// * `key` - 32-byte (256-bit) key.
+    /// * `nonce` - 24-byte (192-bit) extended nonce, safe to choose at random.
+    ///
+    /// # Example
+    /// ```
+    /// use fast_chacha::FastChaCha20;
+    ///
+    /// let mut cipher = FastChaCha20::new_xchacha20(&[0u8; 32], &[0u8; 24]);
+    /// let mut data = [1u8, 2, 3, 4, 5];
+    /// cipher.apply_keystream(&mut data);
+    /// ```
+    pub fn new_xchacha20(key: &[u8; 32], nonce: &[u8; 24]) -> Self {
+        Self::new_xchacha(key, nonce, 10)
+    }
+
+    /// Creates an XChaCha instance whose HChaCha subkey derivation uses `double_rounds`.
+    ///
+    /// The keystream must then be generated with the same round count, i.e. through
+    /// [`FastChaCha20::apply_keystream_pure`] unless `double_rounds` is 10.
+    pub(crate) fn new_xchacha(key: &[u8; 32], nonce: &[u8; 24], double_rounds: usize) -> Self {
+        let mut cipher = Self::new(key, &[0u8; 12]);
+
+        let prefix = [
+            u32::from_le_bytes([nonce[0], nonce[1], nonce[2], nonce[3]]),
+            u32::from_le_bytes([nonce[4], nonce[5], nonce[6], nonce[7]]),
+            u32::from_le_bytes([nonce[8], nonce[9], nonce[10], nonce[11]]),
+            u32::from_le_bytes([nonce[12], nonce[13], nonce[14], nonce[15]]),
+        ];
+        cipher.key_words = fallback_chacha20::hchacha(&cipher.key_words, &prefix, double_rounds);
+        cipher.counter = [
+            0,
+            0,
+            u32::from_le_bytes([nonce[16], nonce[17], nonce[18], nonce[19]]),
+            u32::from_le_bytes([nonce[20], nonce[21], nonce[22], nonce[23]]),
+        ];
+
+        cipher
+    }
+
+    /// XORs `data` with the keystream starting at the beginning of block `counter[0]`.
+    ///
+    /// `double_rounds` selects the pure Rust fallback with the given number of double rounds;
+    /// `None` dispatches to the fastest available implementation.
+    #[inline(always)]
+    fn xor_keystream(&self, data: &mut [u8], counter: [u32; 4], double_rounds: Option<usize>) {
+        let mut counter = counter;
+        match double_rounds {
+            None => unsafe {
+                ChaCha20_ctr32(
+                    data.as_mut_ptr(),
+                    data.as_mut_ptr(),
+                    data.len(),
+                    self.key_words.as_ptr(),
+                    counter.as_mut_ptr(),
+                )
+            },
+            Some(rounds) => {
+                fallback(data, data.len(), false, &self.key_words, &mut counter, rounds);
+            }
+        }
+    }
+
+    /// XORs the keystream at the current position into `output` and advances the position by
+    /// `output.len()`.
+    ///
+    /// Reads from `input` when given, which must be as long as `output`, and from `output`
+    /// itself otherwise.
+    fn process(&mut self, input: Option<&[u8]>, output: &mut [u8], double_rounds: Option<usize>) {
+        let (mut input, mut output) = (input, output);
+
+        // Finish a block that a previous call left partially consumed.
+        if self.offset != 0 && !output.is_empty() {
+            let mut block = [0u8; 64];
+            self.xor_keystream(&mut block, self.counter, double_rounds);
+            let n = (64 - self.offset).min(output.len());
+            for (i, k) in (0..n).zip(&block[self.offset..]) {
+                output[i] = input.map_or(output[i], |input| input[i]) ^ *k;
+            }
+            self.advance(n);
+            input = input.map(|input| &input[n..]);
+            output = &mut output[n..];
+        }
+
+        if output.is_empty() {
+            return;
+        }
+        match (input, double_rounds) {
+            (Some(input), None) => unsafe {
+                ChaCha20_ctr32(
+                    output.as_mut_ptr(),
+                    input.as_ptr(),
+                    output.len(),
+                    self.key_words.as_ptr(),
+                    self.counter.as_ptr(),
+                )
+            },
+            (Some(input), Some(_)) => {
+                output.copy_from_slice(input);
+                self.xor_keystream(output, self.counter, double_rounds);
+            }
+            (None, _) => self.xor_keystream(output, self.counter, double_rounds),
+        }
+        self.advance(output.len());
+    }
+
+    /// Moves the stream position forward by `len` bytes.
+    #[inline(always)]
+    fn advance(&mut self, len: usize) {
+        let total = self.offset + len;
+        self.counter[0] = self.counter[0].wrapping_add((total / 64) as u32);
+        self.offset = total % 64;
     }
 
     /// Applies the ChaCha20 keystream to the given data in-place, using the fastest available implementation.
@@ -245,15 +424,36 @@ impl FastChaCha20 {
             return;
         }
 
-        unsafe {
-            ChaCha20_ctr32(
-                data.as_mut_ptr(),
-                data.as_mut_ptr(),
-                data.len(),
-                self.key_words.as_ptr(),
-                self.counter.as_mut_ptr(),
-            )
+        self.process(None, data, None);
+    }
+
+    /// Writes `input` XORed with the keystream to `output`, using the fastest available
+    /// implementation, and advances the position by `input.len()`.
+    ///
+    /// Equivalent to copying `input` to `output` and calling
+    /// [`FastChaCha20::apply_keystream`], without the extra pass over memory.
+    ///
+    /// # Arguments
+    /// * `input` - Data to encrypt/decrypt.
+    /// * `output` - Receives the result; must be as long as `input`.
+    ///
+    /// # Errors
+    /// [`Error::InvalidLength`] if the buffers have different lengths.
+    ///
+    /// # Example
+    /// ```
+    /// use fast_chacha::FastChaCha20;
+    ///
+    /// let mut cipher = FastChaCha20::new(&[0u8; 32], &[0u8; 12]);
+    /// let mut out = [0u8; 5];
+    /// cipher.apply_keystream_b2b(&[1, 2, 3, 4, 5], &mut out).unwrap();
+    /// ```
+    pub fn apply_keystream_b2b(&mut self, input: &[u8], output: &mut [u8]) -> Result<(), Error> {
+        if input.len() != output.len() {
+            return Err(Error::InvalidLength);
         }
+        self.process(Some(input), output, None);
+        Ok(())
     }
 
     /// Applies the ChaCha20 keystream using the pure Rust fallback implementation.
@@ -276,16 +476,19 @@ impl FastChaCha20 {
         if data.is_empty() {
             return;
         }
-        // Avoid aliasing mutable and immutable borrows by splitting the slice
-        fallback(data, data.len(), false, &self.key_words, &mut self.counter, double_rounds);
+        self.process(None, data, Some(double_rounds));
     }
 
+    /// Overwrites `data` with raw keystream bytes using the pure Rust fallback implementation.
+    ///
+    /// # Arguments
+    /// * `data` - Buffer to fill with keystream.
     pub fn keystream_only(&mut self, data: &mut [u8]) {
         if data.is_empty() {
             return;
         }
-        // Avoid aliasing mutable and immutable borrows by splitting the slice
-        fallback(data, data.len(), true, &self.key_words, &mut self.counter, 10);
+        data.fill(0);
+        self.process(None, data, Some(10));
     }
 
     /// Resets the internal counter to zero.
@@ -299,13 +502,19 @@ impl FastChaCha20 {
     /// ```
     pub fn reset(&mut self) {
         self.counter[0] = 0;
+        self.offset = 0;
     }
 
+    /// Sets the block counter, positioning the stream at the start of that block.
+    ///
+    /// # Arguments
+    /// * `counter` - Block index (each block is 64 bytes).
     pub fn set_counter(&mut self, counter: u32) {
         self.counter[0] = counter;
+        self.offset = 0;
     }
 
-    /// Sets the internal counter to the position corresponding to the given byte offset.
+    /// Sets the stream position to the given byte offset.
     ///
     /// # Arguments
     /// * `pos` - Byte position to seek to.
@@ -319,6 +528,7 @@ impl FastChaCha20 {
     /// ```
     pub fn seek(&mut self, pos: u64) {
         self.counter[0] = (pos / 64) as u32;
+        self.offset = (pos % 64) as usize;
     }
 
     /// Returns the current byte position in the stream.
@@ -331,7 +541,7 @@ impl FastChaCha20 {
     /// let pos = cipher.current_pos();
     /// ```
     pub fn current_pos(&self) -> u64 {
-        (self.counter[0] as u64) * 64
+        (self.counter[0] as u64) * 64 + self.offset as u64
     }
 
     /// Creates a new FastChaCha20 instance with a custom initial counter value.
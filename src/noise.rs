@@ -0,0 +1,187 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Noise Protocol Framework `CipherState` for the `ChaChaPoly` cipher (Noise rev. 34, §5.1).
+//!
+//! `ENCRYPT(k, n, ad, plaintext)` is ChaCha20-Poly1305 with the nonce `0u32 || n.to_le_bytes()`.
+//! The nonce `2^64 - 1` is reserved for `REKEY`; once `n` reaches it, encryption and
+//! decryption fail with [`Error::NonceExhausted`]. `SymmetricState` and `HandshakeState`
+//! (hashing, HKDF, DH) belong to the caller.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::noise::{CipherState, TAG_BYTES};
+//!
+//! let mut initiator = CipherState::new();
+//! let mut responder = CipherState::new();
+//! initiator.initialize_key(Some(&[5u8; 32]));
+//! responder.initialize_key(Some(&[5u8; 32]));
+//!
+//! let mut message = [0u8; 5 + TAG_BYTES];
+//! let n = initiator.encrypt_with_ad(b"h", b"hello", &mut message).unwrap();
+//!
+//! let mut plaintext = [0u8; 5];
+//! let m = responder.decrypt_with_ad(b"h", &message[..n], &mut plaintext).unwrap();
+//! assert_eq!(&plaintext[..m], b"hello");
+//! ```
+
+use crate::aead::ChaCha20Poly1305;
+pub use crate::aead::{KEY_BYTES, TAG_BYTES};
+use crate::Error;
+
+/// Nonce reserved for `REKEY`.
+const MAX_NONCE: u64 = u64::MAX;
+
+/// Builds the `ChaChaPoly` nonce for `n`.
+#[inline(always)]
+fn nonce(n: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[4..].copy_from_slice(&n.to_le_bytes());
+    nonce
+}
+
+/// A Noise `CipherState`: an optional key `k` and a 64-bit nonce `n`.
+///
+/// Without a key (`HasKey() == false`), encryption and decryption copy the input unchanged,
+/// as the specification requires during the first handshake messages.
+#[derive(Clone, Default)]
+pub struct CipherState {
+    k: Option<[u8; KEY_BYTES]>,
+    n: u64,
+}
+
+impl CipherState {
+    /// Creates a cipher state with no key.
+    pub fn new() -> Self {
+        Self { k: None, n: 0 }
+    }
+
+    /// `InitializeKey(key)`: sets `k` and resets `n` to 0. `None` means "empty".
+    pub fn initialize_key(&mut self, key: Option<&[u8; KEY_BYTES]>) {
+        self.k = key.copied();
+        self.n = 0;
+    }
+
+    /// `HasKey()`: returns `true` if `k` is non-empty.
+    pub fn has_key(&self) -> bool {
+        self.k.is_some()
+    }
+
+    /// `SetNonce(nonce)`: sets `n`.
+    pub fn set_nonce(&mut self, n: u64) {
+        self.n = n;
+    }
+
+    /// Returns the nonce the next operation will use.
+    pub fn nonce(&self) -> u64 {
+        self.n
+    }
+
+    /// `EncryptWithAd(ad, plaintext)`: encrypts into `out` and returns the ciphertext length.
+    ///
+    /// With a key the ciphertext is `plaintext.len() + TAG_BYTES` bytes and `n` is
+    /// incremented; without one the plaintext is copied as is.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidLength`] if `out` is too small.
+    /// - [`Error::NonceExhausted`] if `n` reached `2^64 - 1`.
+    pub fn encrypt_with_ad(
+        &mut self,
+        ad: &[u8],
+        plaintext: &[u8],
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        let Some(k) = self.k else {
+            out.get_mut(..plaintext.len()).ok_or(Error::InvalidLength)?.copy_from_slice(plaintext);
+            return Ok(plaintext.len());
+        };
+        let len = plaintext.len() + TAG_BYTES;
+        if out.len() < len {
+            return Err(Error::InvalidLength);
+        }
+        if self.n == MAX_NONCE {
+            return Err(Error::NonceExhausted);
+        }
+
+        let (data, tag) = out[..len].split_at_mut(plaintext.len());
+        data.copy_from_slice(plaintext);
+        tag.copy_from_slice(&ChaCha20Poly1305::new(&k).encrypt_in_place_detached(
+            &nonce(self.n),
+            ad,
+            data,
+        )?);
+        self.n += 1;
+        Ok(len)
+    }
+
+    /// `DecryptWithAd(ad, ciphertext)`: decrypts into `out` and returns the plaintext length.
+    ///
+    /// `n` is only incremented when authentication succeeds.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidLength`] if `ciphertext` is shorter than a tag or `out` is too small.
+    /// - [`Error::NonceExhausted`] if `n` reached `2^64 - 1`.
+    /// - [`Error::AuthenticationFailed`] if the tag does not verify.
+    pub fn decrypt_with_ad(
+        &mut self,
+        ad: &[u8],
+        ciphertext: &[u8],
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        let Some(k) = self.k else {
+            out.get_mut(..ciphertext.len())
+                .ok_or(Error::InvalidLength)?
+                .copy_from_slice(ciphertext);
+            return Ok(ciphertext.len());
+        };
+        let len = ciphertext.len().checked_sub(TAG_BYTES).ok_or(Error::InvalidLength)?;
+        if out.len() < len {
+            return Err(Error::InvalidLength);
+        }
+        if self.n == MAX_NONCE {
+            return Err(Error::NonceExhausted);
+        }
+
+        let (data, tag) = ciphertext.split_at(len);
+        let out = &mut out[..len];
+        out.copy_from_slice(data);
+        let aead = ChaCha20Poly1305::new(&k);
+        if let Err(e) =
+            aead.decrypt_in_place_detached(&nonce(self.n), ad, out, tag.try_into().unwrap())
+        {
+            // Do not leave unauthenticated ciphertext in the caller's buffer.
+            out.fill(0);
+            return Err(e);
+        }
+        self.n += 1;
+        Ok(len)
+    }
+
+    /// `Rekey()`: replaces `k` with the first 32 bytes of `ENCRYPT(k, 2^64 - 1, "", zeros)`.
+    ///
+    /// `n` is left unchanged. Does nothing without a key.
+    pub fn rekey(&mut self) {
+        if let Some(k) = self.k.as_mut() {
+            let mut new_key = [0u8; KEY_BYTES];
+            // 32 bytes are always within the length limit.
+            ChaCha20Poly1305::new(k)
+                .encrypt_in_place_detached(&nonce(MAX_NONCE), &[], &mut new_key)
+                .unwrap();
+            *k = new_key;
+        }
+    }
+}
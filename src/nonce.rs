@@ -0,0 +1,304 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Nonce sequences that never repeat for a key, and an optional reuse detector.
+//!
+//! Reusing a (key, nonce) pair with ChaCha20 leaks the XOR of the two plaintexts, and with
+//! ChaCha20-Poly1305 it also lets an attacker forge messages. The types below produce
+//! nonces through the [`NonceSequence`] trait:
+//!
+//! - [`CounterNonce`]: `prefix (4) || counter (BE64)`, in memory. Suited to one sender
+//!   per key and prefix.
+//! - [`RandomXNonce`] (`getrandom` feature): 24 random bytes for XChaCha20, safe to use
+//!   from any number of senders without coordination.
+//! - [`PersistentCounterNonce`] (`std` feature): a [`CounterNonce`] whose position is
+//!   reserved in a file, so restarting the process (or crashing) never reuses a nonce.
+//!
+//! The AEADs draw from a sequence with `encrypt_next_in_place_detached`. With the
+//! `nonce-registry` feature, debug builds record every (key, nonce) pair sealed that way and
+//! panic on the first repeat within the process; see [`registry`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::aead::ChaCha20Poly1305;
+//! use fast_chacha::nonce::CounterNonce;
+//!
+//! let aead = ChaCha20Poly1305::new(&[4u8; 32]);
+//! let mut nonces = CounterNonce::new([0, 0, 0, 1]);
+//!
+//! for message in [&b"first"[..], b"second"] {
+//!     let mut buf = message.to_vec();
+//!     let (nonce, tag) =
+//!         aead.encrypt_next_in_place_detached(&mut nonces, &[], &mut buf).unwrap();
+//!     aead.decrypt_in_place_detached(&nonce, &[], &mut buf, &tag).unwrap();
+//! }
+//! ```
+
+use crate::Error;
+
+/// Size of the fixed prefix of a [`CounterNonce`], in bytes.
+pub const PREFIX_BYTES: usize = 4;
+
+/// A source of nonces that never repeat for the key they are used with.
+pub trait NonceSequence<const N: usize> {
+    /// Returns the next nonce.
+    ///
+    /// # Errors
+    /// [`Error::NonceExhausted`] once the sequence is used up; the key must be replaced.
+    /// Implementations that need the OS or the file system report their failures too.
+    fn next_nonce(&mut self) -> Result<[u8; N], Error>;
+}
+
+/// Monotonic 96-bit nonces: a 4-byte prefix followed by a big-endian 64-bit counter.
+///
+/// Give every sender that shares a key its own prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CounterNonce {
+    prefix: [u8; PREFIX_BYTES],
+    /// Counter of the next nonce; `None` once `u64::MAX` has been handed out.
+    next: Option<u64>,
+}
+
+impl CounterNonce {
+    /// Creates a sequence starting at counter 0.
+    ///
+    /// # Arguments
+    /// * `prefix` - Fixed first 4 bytes of every nonce.
+    pub fn new(prefix: [u8; PREFIX_BYTES]) -> Self {
+        Self::starting_at(prefix, 0)
+    }
+
+    /// Creates a sequence whose first nonce uses `counter`.
+    ///
+    /// # Arguments
+    /// * `prefix` - Fixed first 4 bytes of every nonce.
+    /// * `counter` - Counter of the first nonce.
+    pub fn starting_at(prefix: [u8; PREFIX_BYTES], counter: u64) -> Self {
+        Self { prefix, next: Some(counter) }
+    }
+
+    /// Returns the counter the next nonce will use, or `None` if the sequence is exhausted.
+    pub fn counter(&self) -> Option<u64> {
+        self.next
+    }
+}
+
+impl NonceSequence<12> for CounterNonce {
+    fn next_nonce(&mut self) -> Result<[u8; 12], Error> {
+        let counter = self.next.ok_or(Error::NonceExhausted)?;
+        self.next = counter.checked_add(1);
+
+        let mut nonce = [0u8; 12];
+        nonce[..PREFIX_BYTES].copy_from_slice(&self.prefix);
+        nonce[PREFIX_BYTES..].copy_from_slice(&counter.to_be_bytes());
+        Ok(nonce)
+    }
+}
+
+/// Random 192-bit nonces for XChaCha20 and XChaCha20-Poly1305, from the OS generator.
+///
+/// With 192 bits a collision is negligible even after 2^64 messages under one key.
+#[cfg(feature = "getrandom")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomXNonce;
+
+#[cfg(feature = "getrandom")]
+impl NonceSequence<24> for RandomXNonce {
+    /// # Errors
+    /// [`Error::RandomUnavailable`] if the OS random number generator fails.
+    fn next_nonce(&mut self) -> Result<[u8; 24], Error> {
+        let mut nonce = [0u8; 24];
+        getrandom::fill(&mut nonce).map_err(|_| Error::RandomUnavailable)?;
+        Ok(nonce)
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::persistent::PersistentCounterNonce;
+
+#[cfg(feature = "std")]
+mod persistent {
+    use std::fs::{self, File};
+    use std::io::{self, Write};
+    use std::path::{Path, PathBuf};
+
+    use super::{CounterNonce, NonceSequence, PREFIX_BYTES};
+    use crate::Error;
+
+    /// Number of counters reserved per file update by [`PersistentCounterNonce::open`].
+    pub const DEFAULT_RESERVATION: u64 = 1 << 16;
+
+    /// A [`CounterNonce`] that survives restarts.
+    ///
+    /// The state file holds, as 8 little-endian bytes, the first counter not yet reserved.
+    /// Counters are reserved in blocks: the file is advanced (and synced) before any
+    /// counter of a block is handed out, so a crash skips the rest of the block instead of
+    /// reusing it. A missing file starts at counter 0.
+    ///
+    /// Only one `PersistentCounterNonce` may use a state file at a time.
+    #[derive(Debug)]
+    pub struct PersistentCounterNonce {
+        path: PathBuf,
+        inner: CounterNonce,
+        /// First counter of the next block; the current block ends just before it.
+        reserved_until: u64,
+        reservation: u64,
+    }
+
+    impl PersistentCounterNonce {
+        /// Opens (or creates) a state file with the [`DEFAULT_RESERVATION`] block size.
+        ///
+        /// # Arguments
+        /// * `path` - State file.
+        /// * `prefix` - Fixed first 4 bytes of every nonce.
+        ///
+        /// # Errors
+        /// I/O errors reading or writing the state file, or [`io::ErrorKind::InvalidData`]
+        /// if it is not 8 bytes long.
+        pub fn open(path: impl AsRef<Path>, prefix: [u8; PREFIX_BYTES]) -> io::Result<Self> {
+            Self::with_reservation(path, prefix, DEFAULT_RESERVATION)
+        }
+
+        /// Opens (or creates) a state file, reserving `reservation` counters per update.
+        ///
+        /// Larger reservations mean fewer file writes but more counters skipped per restart.
+        ///
+        /// # Errors
+        /// Same as [`PersistentCounterNonce::open`]; `reservation` must not be zero.
+        pub fn with_reservation(
+            path: impl AsRef<Path>,
+            prefix: [u8; PREFIX_BYTES],
+            reservation: u64,
+        ) -> io::Result<Self> {
+            if reservation == 0 {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty reservation"));
+            }
+            let path = path.as_ref().to_path_buf();
+            let start = match fs::read(&path) {
+                Ok(bytes) => u64::from_le_bytes(bytes.try_into().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidData, "nonce state file is corrupt")
+                })?),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+                Err(e) => return Err(e),
+            };
+
+            let mut this = Self {
+                path,
+                inner: CounterNonce::starting_at(prefix, start),
+                reserved_until: start,
+                reservation,
+            };
+            this.reserve()?;
+            Ok(this)
+        }
+
+        /// Returns the counter the next nonce will use, or `None` if the sequence is
+        /// exhausted.
+        pub fn counter(&self) -> Option<u64> {
+            self.inner.counter()
+        }
+
+        /// Writes the end of a new block to the state file before it is used.
+        fn reserve(&mut self) -> io::Result<()> {
+            let Some(start) = self.inner.counter() else {
+                return Ok(());
+            };
+            let end = start.saturating_add(self.reservation);
+
+            // Write a sibling file and rename it over the state file, so a crash leaves
+            // either the old or the new value.
+            let mut tmp = self.path.clone().into_os_string();
+            tmp.push(".tmp");
+            let mut file = File::create(&tmp)?;
+            file.write_all(&end.to_le_bytes())?;
+            file.sync_all()?;
+            fs::rename(&tmp, &self.path)?;
+            if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                // Persist the rename itself where the platform allows it.
+                if let Ok(dir) = File::open(dir) {
+                    let _ = dir.sync_all();
+                }
+            }
+
+            self.reserved_until = end;
+            Ok(())
+        }
+    }
+
+    impl NonceSequence<12> for PersistentCounterNonce {
+        /// # Errors
+        /// - [`Error::NonceExhausted`] once counter `u64::MAX - 1` has been used.
+        /// - [`Error::Io`] if the next block cannot be reserved in the state file.
+        fn next_nonce(&mut self) -> Result<[u8; 12], Error> {
+            match self.inner.counter() {
+                // The last counter value is the "reserved until" marker and never used.
+                Some(u64::MAX) | None => return Err(Error::NonceExhausted),
+                Some(counter) if counter >= self.reserved_until => {
+                    self.reserve().map_err(|_| Error::Io)?
+                }
+                Some(_) => {}
+            }
+            self.inner.next_nonce()
+        }
+    }
+}
+
+/// Debug-build detector of repeated (key, nonce) pairs, enabled by `nonce-registry`.
+///
+/// The AEADs record the pair of every seal whose nonce comes from a [`NonceSequence`]
+/// (`encrypt_next_in_place_detached`). Seals with an explicit nonce are not recorded:
+/// protocols derive some of them deterministically and repeat them on purpose, e.g. a Noise
+/// rekey seals zeros under the same key and nonce on both peers. Call [`registry::record`]
+/// for raw [`crate::FastChaCha20`] streams, where the crate cannot tell encryption from
+/// decryption. Pairs are stored as 64-bit SipHash digests under a random per-process key,
+/// never in the clear. In release builds (without `debug_assertions`) recording does
+/// nothing.
+#[cfg(feature = "nonce-registry")]
+pub mod registry {
+    use std::collections::hash_map::RandomState;
+    use std::collections::HashSet;
+    use std::hash::{BuildHasher, Hasher};
+    use std::sync::{Mutex, OnceLock};
+
+    struct Registry {
+        hasher: RandomState,
+        seen: Mutex<HashSet<u64>>,
+    }
+
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+    /// Records that `key` is about to encrypt under `nonce`.
+    ///
+    /// # Panics
+    /// In debug builds, panics if the same pair was recorded before in this process.
+    pub fn record(key: &[u8; 32], nonce: &[u8]) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        let registry = REGISTRY.get_or_init(|| Registry {
+            hasher: RandomState::new(),
+            seen: Mutex::new(HashSet::new()),
+        });
+        let mut hasher = registry.hasher.build_hasher();
+        hasher.write(key);
+        hasher.write(nonce);
+        let digest = hasher.finish();
+
+        let fresh = registry.seen.lock().unwrap_or_else(|e| e.into_inner()).insert(digest);
+        assert!(fresh, "fast_chacha: nonce reused with the same key");
+    }
+}
@@ -0,0 +1,68 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Multi-threaded keystream application using rayon.
+//!
+//! The ChaCha20 keystream is random-access: block `n` only depends on the key, the nonce
+//! and `counter[0] + n`. Large buffers are therefore split at block-aligned boundaries and
+//! each chunk is processed by `ChaCha20_ctr32` on its own thread with the matching counter.
+
+use rayon::prelude::*;
+
+use crate::FastChaCha20;
+
+/// Bytes handed to a single worker; a multiple of the 64-byte block size.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+impl FastChaCha20 {
+    /// Applies the ChaCha20 keystream to `data` in-place using all rayon worker threads.
+    ///
+    /// The result and the final stream position are identical to calling
+    /// [`FastChaCha20::apply_keystream`] on the whole buffer. Buffers shorter than two
+    /// chunks (128 KiB) are processed on the calling thread.
+    ///
+    /// # Arguments
+    /// * `data` - Mutable buffer to encrypt/decrypt.
+    ///
+    /// # Example
+    /// ```
+    /// use fast_chacha::FastChaCha20;
+    ///
+    /// let mut cipher = FastChaCha20::new(&[0u8; 32], &[0u8; 12]);
+    /// let mut data = vec![0u8; 1 << 20];
+    /// cipher.apply_keystream_parallel(&mut data);
+    /// ```
+    pub fn apply_keystream_parallel(&mut self, data: &mut [u8]) {
+        if data.len() < 2 * CHUNK_SIZE {
+            self.apply_keystream(data);
+            return;
+        }
+
+        // Finish a partially consumed block first so the rest starts on a block boundary.
+        let head = (64 - self.offset) % 64;
+        let (head, rest) = data.split_at_mut(head);
+        self.apply_keystream(head);
+
+        let this = &*self;
+        rest.par_chunks_mut(CHUNK_SIZE).enumerate().for_each(|(i, chunk)| {
+            let mut counter = this.counter;
+            counter[0] = counter[0].wrapping_add((i * (CHUNK_SIZE / 64)) as u32);
+            this.xor_keystream(chunk, counter, None);
+        });
+
+        self.advance(rest.len());
+    }
+}
@@ -0,0 +1,280 @@
+// Copyright 2025 sh0rch <sh0rch@iwl.dev>
+// Licensed under the MIT License.
+// You may obtain a copy of the License at
+//
+//     https://opensource.org/licenses/MIT
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Poly1305 one-time authenticator (Pure Rust)
+//!
+//! A minimal, dependency-free, constant-time implementation of Poly1305 (RFC 8439 §2.5),
+//! using the 32-bit "donna" representation with five 26-bit limbs. It is the MAC half of
+//! every ChaCha20-Poly1305 construction in this crate.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use fast_chacha::poly1305::Poly1305;
+//! let key = [1u8; 32];
+//! let mut mac = Poly1305::new(&key);
+//! mac.update(b"message");
+//! let tag = mac.finalize();
+//! ```
+//!
+//! # Security
+//!
+//! A Poly1305 key must never be used for more than one message. The AEAD constructions
+//! derive a fresh key from the ChaCha20 keystream for every message.
+
+/// Size of a Poly1305 block and of the resulting tag, in bytes.
+pub const BLOCK_SIZE: usize = 16;
+
+/// Mask selecting the low 26 bits of a limb.
+const MASK26: u32 = 0x3ffffff;
+
+/// Zero bytes used for padding.
+const ZEROS: [u8; BLOCK_SIZE] = [0u8; BLOCK_SIZE];
+
+/// Reads a little-endian `u32` from the first four bytes of `b`.
+#[inline(always)]
+fn le32(b: &[u8]) -> u32 {
+    u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+}
+
+/// Incremental Poly1305 state.
+#[derive(Clone)]
+pub struct Poly1305 {
+    /// Clamped multiplier `r` in 26-bit limbs.
+    r: [u32; 5],
+    /// Accumulator `h` in 26-bit limbs.
+    h: [u32; 5],
+    /// Final addend `s` as four 32-bit words.
+    pad: [u32; 4],
+    /// Partial block waiting for more input.
+    buffer: [u8; BLOCK_SIZE],
+    /// Number of valid bytes in `buffer`.
+    leftover: usize,
+}
+
+impl Poly1305 {
+    /// Creates a new authenticator from a 32-byte one-time key (`r || s`).
+    ///
+    /// # Arguments
+    /// * `key` - 32-byte one-time key; `r` is clamped as required by the specification.
+    pub fn new(key: &[u8; 32]) -> Self {
+        let mut state = Self::from_r(key[..16].try_into().unwrap());
+        state.pad = [le32(&key[16..]), le32(&key[20..]), le32(&key[24..]), le32(&key[28..])];
+        state
+    }
+
+    /// Creates an authenticator with the given `r` and `s = 0`.
+    pub(crate) fn from_r(r: &[u8; 16]) -> Self {
+        Self {
+            r: [
+                le32(&r[0..]) & 0x3ffffff,
+                (le32(&r[3..]) >> 2) & 0x3ffff03,
+                (le32(&r[6..]) >> 4) & 0x3ffc0ff,
+                (le32(&r[9..]) >> 6) & 0x3f03fff,
+                (le32(&r[12..]) >> 8) & 0x00fffff,
+            ],
+            h: [0; 5],
+            pad: [0; 4],
+            buffer: [0; BLOCK_SIZE],
+            leftover: 0,
+        }
+    }
+
+    /// Absorbs whole 16-byte blocks; `hibit` is `1 << 24` for full message blocks.
+    fn blocks(&mut self, m: &[u8], hibit: u32) {
+        let [r0, r1, r2, r3, r4] = self.r;
+        let (s1, s2, s3, s4) = (r1 * 5, r2 * 5, r3 * 5, r4 * 5);
+        let [mut h0, mut h1, mut h2, mut h3, mut h4] = self.h;
+
+        for block in m.chunks_exact(BLOCK_SIZE) {
+            // h += m
+            h0 += le32(&block[0..]) & MASK26;
+            h1 += (le32(&block[3..]) >> 2) & MASK26;
+            h2 += (le32(&block[6..]) >> 4) & MASK26;
+            h3 += (le32(&block[9..]) >> 6) & MASK26;
+            h4 += (le32(&block[12..]) >> 8) | hibit;
+
+            // h *= r
+            let mul = |a: u32, b: u32| a as u64 * b as u64;
+            let d0 = mul(h0, r0) + mul(h1, s4) + mul(h2, s3) + mul(h3, s2) + mul(h4, s1);
+            let mut d1 = mul(h0, r1) + mul(h1, r0) + mul(h2, s4) + mul(h3, s3) + mul(h4, s2);
+            let mut d2 = mul(h0, r2) + mul(h1, r1) + mul(h2, r0) + mul(h3, s4) + mul(h4, s3);
+            let mut d3 = mul(h0, r3) + mul(h1, r2) + mul(h2, r1) + mul(h3, r0) + mul(h4, s4);
+            let mut d4 = mul(h0, r4) + mul(h1, r3) + mul(h2, r2) + mul(h3, r1) + mul(h4, r0);
+
+            // (partial) h %= p
+            h0 = d0 as u32 & MASK26;
+            d1 += d0 >> 26;
+            h1 = d1 as u32 & MASK26;
+            d2 += d1 >> 26;
+            h2 = d2 as u32 & MASK26;
+            d3 += d2 >> 26;
+            h3 = d3 as u32 & MASK26;
+            d4 += d3 >> 26;
+            h4 = d4 as u32 & MASK26;
+            h0 += (d4 >> 26) as u32 * 5;
+            h1 += h0 >> 26;
+            h0 &= MASK26;
+        }
+
+        self.h = [h0, h1, h2, h3, h4];
+    }
+
+    /// Absorbs `data` into the authenticator.
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+
+        if self.leftover > 0 {
+            let n = (BLOCK_SIZE - self.leftover).min(data.len());
+            self.buffer[self.leftover..self.leftover + n].copy_from_slice(&data[..n]);
+            self.leftover += n;
+            data = &data[n..];
+            if self.leftover < BLOCK_SIZE {
+                return;
+            }
+            let block = self.buffer;
+            self.blocks(&block, 1 << 24);
+            self.leftover = 0;
+        }
+
+        let full = data.len() - data.len() % BLOCK_SIZE;
+        self.blocks(&data[..full], 1 << 24);
+
+        let rest = &data[full..];
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.leftover = rest.len();
+    }
+
+    /// Pads the input absorbed so far with zeros up to a multiple of 16 bytes.
+    ///
+    /// This is the padding used between the AAD and ciphertext sections of RFC 8439.
+    pub fn pad(&mut self) {
+        if self.leftover > 0 {
+            self.update(&ZEROS[self.leftover..]);
+        }
+    }
+
+    /// Reduces the accumulator fully modulo 2^130 - 5 and returns it as four 32-bit words
+    /// (the value modulo 2^128), without adding `s`.
+    pub(crate) fn reduce(&mut self) -> [u32; 4] {
+        if self.leftover > 0 {
+            let mut block = [0u8; BLOCK_SIZE];
+            block[..self.leftover].copy_from_slice(&self.buffer[..self.leftover]);
+            block[self.leftover] = 1;
+            self.blocks(&block, 0);
+            self.leftover = 0;
+        }
+
+        let [mut h0, mut h1, mut h2, mut h3, mut h4] = self.h;
+
+        // fully carry h
+        h2 += h1 >> 26;
+        h1 &= MASK26;
+        h3 += h2 >> 26;
+        h2 &= MASK26;
+        h4 += h3 >> 26;
+        h3 &= MASK26;
+        h0 += (h4 >> 26) * 5;
+        h4 &= MASK26;
+        h1 += h0 >> 26;
+        h0 &= MASK26;
+
+        // compute h + -p
+        let mut g0 = h0.wrapping_add(5);
+        let mut g1 = h1.wrapping_add(g0 >> 26);
+        g0 &= MASK26;
+        let mut g2 = h2.wrapping_add(g1 >> 26);
+        g1 &= MASK26;
+        let mut g3 = h3.wrapping_add(g2 >> 26);
+        g2 &= MASK26;
+        let mut g4 = h4.wrapping_add(g3 >> 26).wrapping_sub(1 << 26);
+        g3 &= MASK26;
+
+        // select h if h < p, or h + -p if h >= p, without branching
+        let mask = (g4 >> 31).wrapping_sub(1);
+        g0 &= mask;
+        g1 &= mask;
+        g2 &= mask;
+        g3 &= mask;
+        g4 &= mask;
+        let mask = !mask;
+        h0 = (h0 & mask) | g0;
+        h1 = (h1 & mask) | g1;
+        h2 = (h2 & mask) | g2;
+        h3 = (h3 & mask) | g3;
+        h4 = (h4 & mask) | g4;
+
+        // h = h % 2^128
+        [h0 | (h1 << 26), (h1 >> 6) | (h2 << 20), (h2 >> 12) | (h3 << 14), (h3 >> 18) | (h4 << 8)]
+    }
+
+    /// Finishes the computation and returns the 16-byte tag.
+    pub fn finalize(mut self) -> [u8; BLOCK_SIZE] {
+        let h = self.reduce();
+
+        // mac = (h + s) % 2^128
+        let mut tag = [0u8; BLOCK_SIZE];
+        let mut carry = 0u64;
+        for (i, (word, pad)) in h.iter().zip(self.pad.iter()).enumerate() {
+            carry += *word as u64 + *pad as u64;
+            tag[i * 4..i * 4 + 4].copy_from_slice(&(carry as u32).to_le_bytes());
+            carry >>= 32;
+        }
+        tag
+    }
+}
+
+/// Compares two tags in constant time.
+///
+/// Returns `true` if `a` and `b` are equal. The running time does not depend on the
+/// position or number of differing bytes.
+///
+/// # Example
+/// ```rust
+/// use fast_chacha::poly1305::verify_tag;
+/// assert!(verify_tag(&[7u8; 16], &[7u8; 16]));
+/// ```
+pub fn verify_tag(a: &[u8; BLOCK_SIZE], b: &[u8; BLOCK_SIZE]) -> bool {
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    // Keep the optimizer from turning the fold into an early-exit comparison.
+    let diff = core::hint::black_box(diff) as u32;
+    (diff.wrapping_sub(1) >> 31) == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Poly1305;
+
+    /// RFC 8439 §2.5.2 test vector, fed in uneven pieces to exercise buffering.
+    #[test]
+    fn test_poly1305_rfc8439() {
+        let key = [
+            0x85, 0xd6, 0xbe, 0x78, 0x57, 0x55, 0x6d, 0x33, 0x7f, 0x44, 0x52, 0xfe, 0x42, 0xd5,
+            0x06, 0xa8, 0x01, 0x03, 0x80, 0x8a, 0xfb, 0x0d, 0xb2, 0xfd, 0x4a, 0xbf, 0xf6, 0xaf,
+            0x41, 0x49, 0xf5, 0x1b,
+        ];
+        let tag = [
+            0xa8, 0x06, 0x1d, 0xc1, 0x30, 0x51, 0x36, 0xc6, 0xc2, 0x2b, 0x8b, 0xaf, 0x0c, 0x01,
+            0x27, 0xa9,
+        ];
+        let msg = b"Cryptographic Forum Research Group";
+
+        let mut mac = Poly1305::new(&key);
+        mac.update(&msg[..5]);
+        mac.update(&msg[5..21]);
+        mac.update(&msg[21..]);
+        assert_eq!(mac.finalize(), tag);
+    }
+}
@@ -0,0 +1,165 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Checkpointing of a [`FastChaCha20`] stream position, without the key.
+//!
+//! A [`Position`] holds the nonce, the block counter and the offset inside the current
+//! block: everything needed, together with the key, to continue a stream exactly where it
+//! stopped, e.g. after a restart. The key is deliberately left out, so positions can be
+//! stored next to the data they describe.
+//!
+//! [`Position::to_bytes`] produces a stable 18-byte encoding:
+//!
+//! | Bytes | Field |
+//! |-------|-------|
+//! | 0 | format version, currently `1` |
+//! | 1..13 | nonce |
+//! | 13..17 | block counter, little-endian |
+//! | 17 | offset inside the block, below 64 |
+//!
+//! With the `serde` feature, [`Position`] also implements `Serialize` and `Deserialize`.
+//!
+//! A single nonce covers 2^32 blocks (256 GiB); longer streams have to move to a new
+//! nonce, as the chunked formats in this crate do.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::position::Position;
+//! use fast_chacha::FastChaCha20;
+//!
+//! let key = [1u8; 32];
+//! let mut cipher = FastChaCha20::new(&key, &[2u8; 12]);
+//! cipher.apply_keystream(&mut [0u8; 1000]);
+//! let saved = cipher.position().to_bytes();
+//!
+//! // After a restart:
+//! let position = Position::from_bytes(&saved).unwrap();
+//! let mut resumed = FastChaCha20::resume(&key, &position).unwrap();
+//! assert_eq!(resumed.current_pos(), 1000);
+//! ```
+
+use crate::{Error, FastChaCha20};
+
+/// Length of [`Position::to_bytes`], in bytes.
+pub const ENCODED_LEN: usize = 18;
+
+/// Current version of the byte encoding.
+const VERSION: u8 = 1;
+
+/// Position of a ChaCha20 stream: nonce, block counter and offset inside the block.
+///
+/// For XChaCha20 streams the nonce is the 96-bit nonce derived from the 192-bit one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    /// 96-bit nonce of the stream.
+    pub nonce: [u8; 12],
+    /// Block counter of the next keystream byte.
+    pub counter: u32,
+    /// Offset of the next keystream byte inside block `counter`; below 64.
+    pub offset: u8,
+}
+
+impl Position {
+    /// Encodes the position into its stable byte representation.
+    pub fn to_bytes(&self) -> [u8; ENCODED_LEN] {
+        let mut out = [0u8; ENCODED_LEN];
+        out[0] = VERSION;
+        out[1..13].copy_from_slice(&self.nonce);
+        out[13..17].copy_from_slice(&self.counter.to_le_bytes());
+        out[17] = self.offset;
+        out
+    }
+
+    /// Decodes a position produced by [`Position::to_bytes`].
+    ///
+    /// # Arguments
+    /// * `bytes` - Encoded position, exactly [`ENCODED_LEN`] bytes.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidLength`] if `bytes` is not [`ENCODED_LEN`] bytes long.
+    /// - [`Error::InvalidPosition`] if the version is unknown or the offset is out of range.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes: &[u8; ENCODED_LEN] = bytes.try_into().map_err(|_| Error::InvalidLength)?;
+        if bytes[0] != VERSION || bytes[17] >= 64 {
+            return Err(Error::InvalidPosition);
+        }
+        Ok(Self {
+            nonce: bytes[1..13].try_into().unwrap(),
+            counter: u32::from_le_bytes(bytes[13..17].try_into().unwrap()),
+            offset: bytes[17],
+        })
+    }
+}
+
+impl FastChaCha20 {
+    /// Returns the current stream position, without the key.
+    ///
+    /// # Example
+    /// ```
+    /// use fast_chacha::FastChaCha20;
+    ///
+    /// let cipher = FastChaCha20::new(&[0u8; 32], &[7u8; 12]);
+    /// assert_eq!(cipher.position().nonce, [7u8; 12]);
+    /// ```
+    pub fn position(&self) -> Position {
+        let mut nonce = [0u8; 12];
+        for (out, word) in nonce.chunks_exact_mut(4).zip(&self.counter[1..]) {
+            out.copy_from_slice(&word.to_le_bytes());
+        }
+        Position { nonce, counter: self.counter[0], offset: self.offset as u8 }
+    }
+
+    /// Moves the stream to a saved position.
+    ///
+    /// Use this to resume an XChaCha20 stream: create it again with
+    /// [`FastChaCha20::new_xchacha20`], then restore the position.
+    ///
+    /// Only the 12-byte nonce the stream runs on is compared. For XChaCha20 that is
+    /// `[0; 4] || nonce[16..24]`, so a position saved on an XChaCha20 stream whose 24-byte
+    /// nonce shares those last 8 bytes is accepted even though its subkey differs; the
+    /// caller has to keep track of which stream a position belongs to.
+    ///
+    /// # Arguments
+    /// * `position` - Position returned by [`FastChaCha20::position`] on the same stream.
+    ///
+    /// # Errors
+    /// [`Error::InvalidPosition`] if the position's nonce differs from the stream's
+    /// 12-byte nonce, or its offset is out of range.
+    pub fn set_position(&mut self, position: &Position) -> Result<(), Error> {
+        if position.offset >= 64 || position.nonce != self.position().nonce {
+            return Err(Error::InvalidPosition);
+        }
+        self.counter[0] = position.counter;
+        self.offset = usize::from(position.offset);
+        Ok(())
+    }
+
+    /// Recreates a ChaCha20 stream from its key and a saved position.
+    ///
+    /// # Arguments
+    /// * `key` - 32-byte key of the stream.
+    /// * `position` - Position returned by [`FastChaCha20::position`].
+    ///
+    /// # Errors
+    /// [`Error::InvalidPosition`] if the offset is out of range.
+    pub fn resume(key: &[u8; 32], position: &Position) -> Result<Self, Error> {
+        let mut cipher = Self::new(key, &position.nonce);
+        cipher.set_position(position)?;
+        Ok(cipher)
+    }
+}
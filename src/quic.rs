@@ -0,0 +1,227 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! QUIC packet and header protection with `TLS_CHACHA20_POLY1305_SHA256` (RFC 9001 §5).
+//!
+//! - Packet protection is ChaCha20-Poly1305 with the nonce `iv XOR packet_number` and the
+//!   unprotected header as AAD.
+//! - Header protection (§5.4.4) takes a 16-byte sample of the ciphertext starting 4 bytes
+//!   after the packet number field: the first 4 bytes are the little-endian block counter,
+//!   the remaining 12 the nonce, and the mask is the first 5 bytes of that keystream block.
+//!   [`quic_header_mask`] computes it.
+//!
+//! [`PacketProtection`] combines both for complete packets. Key derivation (HKDF) and
+//! header parsing beyond the packet number offset are left to the caller.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::quic::{PacketProtection, TAG_BYTES};
+//!
+//! let keys = PacketProtection::new(&[1u8; 32], &[2u8; 12], &[3u8; 32]);
+//!
+//! // Short header: flags, 4-byte destination connection ID, 2-byte packet number.
+//! let mut packet = vec![0x41, 0xaa, 0xbb, 0xcc, 0xdd, 0x00, 0x07];
+//! packet.extend_from_slice(&[0u8; 20]); // payload
+//! packet.extend_from_slice(&[0u8; TAG_BYTES]);
+//!
+//! keys.encrypt_packet(7, &mut packet, 7).unwrap();
+//! let (pn, header_len) = keys.decrypt_packet(&mut packet, 5, 6).unwrap();
+//! assert_eq!((pn, header_len), (7, 7));
+//! ```
+
+use crate::aead::ChaCha20Poly1305;
+pub use crate::aead::{KEY_BYTES, NONCE_BYTES as IV_BYTES, TAG_BYTES};
+use crate::{Error, FastChaCha20};
+
+/// Size of the ciphertext sample used for header protection, in bytes.
+pub const SAMPLE_BYTES: usize = 16;
+/// Size of the header protection mask, in bytes.
+pub const MASK_BYTES: usize = 5;
+
+/// Largest encodable packet number (2^62 - 1).
+const MAX_PACKET_NUMBER: u64 = (1 << 62) - 1;
+
+/// Computes the ChaCha20 header protection mask (RFC 9001 §5.4.4).
+///
+/// # Arguments
+/// * `hp_key` - 32-byte header protection key.
+/// * `sample` - 16 bytes of ciphertext.
+///
+/// # Example
+/// ```rust
+/// use fast_chacha::quic::quic_header_mask;
+/// let mask = quic_header_mask(&[0u8; 32], &[0u8; 16]);
+/// assert_eq!(mask.len(), 5);
+/// ```
+pub fn quic_header_mask(hp_key: &[u8; KEY_BYTES], sample: &[u8; SAMPLE_BYTES]) -> [u8; MASK_BYTES] {
+    let counter = u32::from_le_bytes(sample[..4].try_into().unwrap());
+    let nonce: [u8; 12] = sample[4..].try_into().unwrap();
+
+    let mut mask = [0u8; MASK_BYTES];
+    FastChaCha20::new_with_counter(*hp_key, nonce, counter).apply_keystream(&mut mask);
+    mask
+}
+
+/// Recovers a full packet number from its truncated encoding (RFC 9000 §A.3).
+fn decode_packet_number(largest_pn: u64, truncated_pn: u64, pn_nbits: u32) -> u64 {
+    let expected = largest_pn.min(MAX_PACKET_NUMBER) + 1;
+    let win = 1u64 << pn_nbits;
+    let hwin = win / 2;
+    let candidate = (expected & !(win - 1)) | truncated_pn;
+
+    if candidate + hwin <= expected && candidate < (1 << 62) - win {
+        candidate + win
+    } else if candidate > expected + hwin && candidate >= win {
+        candidate - win
+    } else {
+        candidate
+    }
+}
+
+/// Packet and header protection keys for one QUIC encryption level and direction.
+#[derive(Clone)]
+pub struct PacketProtection {
+    aead: ChaCha20Poly1305,
+    iv: [u8; IV_BYTES],
+    hp_key: [u8; KEY_BYTES],
+}
+
+impl PacketProtection {
+    /// Creates packet protection from the derived `quic key`, `quic iv` and `quic hp` secrets.
+    ///
+    /// # Arguments
+    /// * `key` - 32-byte packet protection key.
+    /// * `iv` - 12-byte packet protection IV.
+    /// * `hp_key` - 32-byte header protection key.
+    pub fn new(key: &[u8; KEY_BYTES], iv: &[u8; IV_BYTES], hp_key: &[u8; KEY_BYTES]) -> Self {
+        Self { aead: ChaCha20Poly1305::new(key), iv: *iv, hp_key: *hp_key }
+    }
+
+    /// Builds the AEAD nonce for a packet number (RFC 9001 §5.3).
+    fn nonce(&self, pn: u64) -> [u8; IV_BYTES] {
+        let mut nonce = self.iv;
+        for (n, p) in nonce[4..].iter_mut().zip(pn.to_be_bytes()) {
+            *n ^= p;
+        }
+        nonce
+    }
+
+    /// Computes the header protection mask for the packet whose packet number field starts
+    /// at `pn_offset`.
+    fn mask(&self, packet: &[u8], pn_offset: usize) -> Result<[u8; MASK_BYTES], Error> {
+        let start = pn_offset + 4;
+        let sample = packet.get(start..start + SAMPLE_BYTES).ok_or(Error::InvalidLength)?;
+        Ok(quic_header_mask(&self.hp_key, sample.try_into().unwrap()))
+    }
+
+    /// XORs the mask into the flags byte and the `pn_len` packet number bytes.
+    fn apply_mask(packet: &mut [u8], pn_offset: usize, pn_len: usize, mask: &[u8; MASK_BYTES]) {
+        // Long headers protect 4 bits of the first byte, short headers 5.
+        packet[0] ^= mask[0] & if packet[0] & 0x80 != 0 { 0x0f } else { 0x1f };
+        for (b, m) in packet[pn_offset..pn_offset + pn_len].iter_mut().zip(&mask[1..]) {
+            *b ^= m;
+        }
+    }
+
+    /// Encrypts a packet in place and applies header protection.
+    ///
+    /// # Arguments
+    /// * `pn` - Full packet number.
+    /// * `packet` - `header || payload || tag space (16)`. The header must be complete,
+    ///   with the truncated packet number as its last 1–4 bytes and the packet number
+    ///   length encoded in the low two bits of the first byte.
+    /// * `header_len` - Length of the header, packet number included.
+    ///
+    /// # Errors
+    /// [`Error::InvalidLength`] if the header is inconsistent or the packet is too short to
+    /// take a header protection sample.
+    pub fn encrypt_packet(
+        &self,
+        pn: u64,
+        packet: &mut [u8],
+        header_len: usize,
+    ) -> Result<(), Error> {
+        let pn_len = (packet.first().ok_or(Error::InvalidLength)? & 0x03) as usize + 1;
+        if pn > MAX_PACKET_NUMBER
+            || header_len < 1 + pn_len
+            || packet.len() < header_len + TAG_BYTES
+        {
+            return Err(Error::InvalidLength);
+        }
+        let pn_offset = header_len - pn_len;
+        // Check the sample is available before touching the packet.
+        if packet.len() < pn_offset + 4 + SAMPLE_BYTES {
+            return Err(Error::InvalidLength);
+        }
+
+        let (header, body) = packet.split_at_mut(header_len);
+        let (payload, tag) = body.split_at_mut(body.len() - TAG_BYTES);
+        let t = self.aead.encrypt_in_place_detached(&self.nonce(pn), header, payload)?;
+        tag.copy_from_slice(&t);
+
+        let mask = self.mask(packet, pn_offset)?;
+        Self::apply_mask(packet, pn_offset, pn_len, &mask);
+        Ok(())
+    }
+
+    /// Removes header protection, recovers the packet number and decrypts a packet in place.
+    ///
+    /// On success returns the full packet number and the header length; the plaintext
+    /// payload is `packet[header_len..packet.len() - TAG_BYTES]`. On failure the packet is
+    /// left as it was received.
+    ///
+    /// # Arguments
+    /// * `packet` - One complete protected packet.
+    /// * `pn_offset` - Offset of the packet number field, known from parsing the header.
+    /// * `largest_pn` - Largest packet number successfully processed so far in this packet
+    ///   number space (0 if none).
+    ///
+    /// # Errors
+    /// - [`Error::InvalidLength`] if the packet is too short.
+    /// - [`Error::AuthenticationFailed`] if the tag does not verify.
+    pub fn decrypt_packet(
+        &self,
+        packet: &mut [u8],
+        pn_offset: usize,
+        largest_pn: u64,
+    ) -> Result<(u64, usize), Error> {
+        if pn_offset == 0 {
+            return Err(Error::InvalidLength);
+        }
+        let mask = self.mask(packet, pn_offset)?;
+        let flags = packet[0] ^ (mask[0] & if packet[0] & 0x80 != 0 { 0x0f } else { 0x1f });
+        let pn_len = (flags & 0x03) as usize + 1;
+        let header_len = pn_offset + pn_len;
+        if packet.len() < header_len + TAG_BYTES {
+            return Err(Error::InvalidLength);
+        }
+
+        Self::apply_mask(packet, pn_offset, pn_len, &mask);
+        let truncated =
+            packet[pn_offset..header_len].iter().fold(0u64, |acc, &b| acc << 8 | b as u64);
+        let pn = decode_packet_number(largest_pn, truncated, 8 * pn_len as u32);
+
+        let (header, body) = packet.split_at_mut(header_len);
+        let (payload, tag) = body.split_at_mut(body.len() - TAG_BYTES);
+        let tag: &[u8; TAG_BYTES] = (&*tag).try_into().unwrap();
+        if let Err(e) = self.aead.decrypt_in_place_detached(&self.nonce(pn), header, payload, tag) {
+            Self::apply_mask(packet, pn_offset, pn_len, &mask);
+            return Err(e);
+        }
+        Ok((pn, header_len))
+    }
+}
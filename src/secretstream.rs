@@ -0,0 +1,332 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Chunked streaming AEAD compatible with libsodium's `crypto_secretstream_xchacha20poly1305`.
+//!
+//! A stream starts with a 24-byte header (random bytes chosen by the sender) and continues
+//! with a sequence of chunks. Every chunk carries one encrypted tag byte, the ciphertext
+//! and a 16-byte Poly1305 MAC, so each chunk is [`ABYTES`] longer than its plaintext.
+//! After each chunk the internal nonce is updated with the MAC and a 32-bit counter, so
+//! chunks cannot be reordered, dropped or duplicated without detection. A [`Tag::Final`]
+//! chunk marks the end of the stream; [`SecretStreamPull::finish`] reports truncation if
+//! it never arrived.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::secretstream::{SecretStreamPull, SecretStreamPush, Tag, ABYTES};
+//!
+//! let key = [3u8; 32];
+//! let header = [5u8; 24]; // use random bytes in real code
+//!
+//! let mut push = SecretStreamPush::new(&key, &header);
+//! let mut c1 = [0u8; 5 + ABYTES];
+//! let mut c2 = [0u8; 5 + ABYTES];
+//! push.push(b"hello", b"", Tag::Message, &mut c1).unwrap();
+//! push.push(b"world", b"", Tag::Final, &mut c2).unwrap();
+//!
+//! let mut pull = SecretStreamPull::new(&key, &header);
+//! let mut m = [0u8; 5];
+//! assert_eq!(pull.pull(&c1, b"", &mut m).unwrap(), (5, Tag::Message));
+//! assert_eq!(pull.pull(&c2, b"", &mut m).unwrap(), (5, Tag::Final));
+//! pull.finish().unwrap();
+//! ```
+
+use crate::fallback_chacha20::hchacha;
+use crate::poly1305::{verify_tag, Poly1305};
+use crate::{Error, FastChaCha20};
+
+/// Size of the stream header, in bytes.
+pub const HEADER_BYTES: usize = 24;
+/// Size of the key, in bytes.
+pub const KEY_BYTES: usize = 32;
+/// Per-chunk overhead (encrypted tag byte + MAC), in bytes.
+pub const ABYTES: usize = 1 + 16;
+
+/// Zero bytes used for Poly1305 padding.
+const PAD0: [u8; 16] = [0u8; 16];
+
+/// Chunk tag, authenticated and encrypted together with every chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Tag {
+    /// Ordinary chunk.
+    Message = 0,
+    /// End of a logical message inside the stream; does not end the stream.
+    Push = 1,
+    /// Rekey after this chunk.
+    Rekey = 2,
+    /// Last chunk of the stream; also rekeys.
+    Final = 3,
+}
+
+impl Tag {
+    /// Decodes a tag byte; unknown values are treated as authentication failures.
+    fn from_byte(byte: u8) -> Result<Self, Error> {
+        match byte {
+            0 => Ok(Tag::Message),
+            1 => Ok(Tag::Push),
+            2 => Ok(Tag::Rekey),
+            3 => Ok(Tag::Final),
+            _ => Err(Error::AuthenticationFailed),
+        }
+    }
+}
+
+/// Key and nonce state shared by the push and pull sides.
+#[derive(Clone)]
+struct State {
+    /// Current chunk key.
+    key: [u8; 32],
+    /// 4-byte little-endian chunk counter followed by the 8-byte inner nonce.
+    nonce: [u8; 12],
+}
+
+impl State {
+    /// Derives the initial state from the key and the stream header.
+    fn new(key: &[u8; KEY_BYTES], header: &[u8; HEADER_BYTES]) -> Self {
+        let mut key_words = [0u32; 8];
+        for (word, chunk) in key_words.iter_mut().zip(key.chunks_exact(4)) {
+            *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut input = [0u32; 4];
+        for (word, chunk) in input.iter_mut().zip(header[..16].chunks_exact(4)) {
+            *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        let mut subkey = [0u8; 32];
+        for (out, word) in subkey.chunks_exact_mut(4).zip(hchacha(&key_words, &input, 10)) {
+            out.copy_from_slice(&word.to_le_bytes());
+        }
+
+        let mut state = Self { key: subkey, nonce: [0u8; 12] };
+        state.nonce[4..].copy_from_slice(&header[16..]);
+        state.reset_counter();
+        state
+    }
+
+    /// Sets the chunk counter back to 1.
+    fn reset_counter(&mut self) {
+        self.nonce[..4].copy_from_slice(&1u32.to_le_bytes());
+    }
+
+    /// Derives a new key and inner nonce from the current ones.
+    fn rekey(&mut self) {
+        let mut buf = [0u8; 40];
+        buf[..32].copy_from_slice(&self.key);
+        buf[32..].copy_from_slice(&self.nonce[4..]);
+        FastChaCha20::new(&self.key, &self.nonce).apply_keystream(&mut buf);
+        self.key.copy_from_slice(&buf[..32]);
+        self.nonce[4..].copy_from_slice(&buf[32..]);
+        self.reset_counter();
+    }
+
+    /// Starts a chunk: returns the chunk cipher (at block 1) and the MAC with the AD absorbed.
+    fn begin(&self, ad: &[u8]) -> (FastChaCha20, Poly1305) {
+        let mut cipher = FastChaCha20::new(&self.key, &self.nonce);
+        let mut block = [0u8; 64];
+        cipher.apply_keystream(&mut block);
+
+        let mut mac = Poly1305::new(block[..32].try_into().unwrap());
+        mac.update(ad);
+        mac.update(&PAD0[..(16 - ad.len() % 16) % 16]);
+        (cipher, mac)
+    }
+
+    /// Absorbs the ciphertext and lengths and returns the chunk MAC.
+    fn finish_mac(mut mac: Poly1305, ad_len: usize, ciphertext: &[u8]) -> [u8; 16] {
+        mac.update(ciphertext);
+        // libsodium pads by `mlen % 16` bytes here (not to the next multiple of 16).
+        mac.update(&PAD0[..ciphertext.len() % 16]);
+        mac.update(&(ad_len as u64).to_le_bytes());
+        mac.update(&(64 + ciphertext.len() as u64).to_le_bytes());
+        mac.finalize()
+    }
+
+    /// Advances the nonce after a chunk and rekeys when required.
+    fn advance(&mut self, mac: &[u8; 16], tag: u8) {
+        for (n, m) in self.nonce[4..].iter_mut().zip(mac.iter()) {
+            *n ^= *m;
+        }
+        let counter = u32::from_le_bytes(self.nonce[..4].try_into().unwrap()).wrapping_add(1);
+        self.nonce[..4].copy_from_slice(&counter.to_le_bytes());
+        if tag & Tag::Rekey as u8 != 0 || counter == 0 {
+            self.rekey();
+        }
+    }
+}
+
+/// Sending side of a secret stream.
+#[derive(Clone)]
+pub struct SecretStreamPush {
+    state: State,
+}
+
+impl SecretStreamPush {
+    /// Starts a new stream.
+    ///
+    /// # Arguments
+    /// * `key` - 32-byte stream key.
+    /// * `header` - 24 random bytes; must be sent to the receiver and never reused with `key`.
+    pub fn new(key: &[u8; KEY_BYTES], header: &[u8; HEADER_BYTES]) -> Self {
+        Self { state: State::new(key, header) }
+    }
+
+    /// Encrypts one chunk.
+    ///
+    /// # Arguments
+    /// * `msg` - Plaintext of the chunk.
+    /// * `ad` - Additional data authenticated with the chunk (may be empty).
+    /// * `tag` - Chunk tag.
+    /// * `out` - Output buffer of at least `msg.len() + ABYTES` bytes.
+    ///
+    /// # Returns
+    /// Number of bytes written to `out` (`msg.len() + ABYTES`).
+    ///
+    /// # Errors
+    /// [`Error::InvalidLength`] if `out` is too small.
+    pub fn push(
+        &mut self,
+        msg: &[u8],
+        ad: &[u8],
+        tag: Tag,
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        let len = msg.len() + ABYTES;
+        if out.len() < len {
+            return Err(Error::InvalidLength);
+        }
+
+        let (mut cipher, mut mac) = self.state.begin(ad);
+
+        let mut block = [0u8; 64];
+        block[0] = tag as u8;
+        cipher.apply_keystream(&mut block);
+        mac.update(&block);
+        out[0] = block[0];
+
+        let (body, rest) = out[1..].split_at_mut(msg.len());
+        body.copy_from_slice(msg);
+        cipher.apply_keystream(body);
+
+        let tag_bytes = State::finish_mac(mac, ad.len(), body);
+        rest[..16].copy_from_slice(&tag_bytes);
+
+        self.state.advance(&tag_bytes, tag as u8);
+        Ok(len)
+    }
+
+    /// Forces a rekey, as `crypto_secretstream_xchacha20poly1305_rekey` does.
+    ///
+    /// The receiver must call [`SecretStreamPull::rekey`] at the same point of the stream.
+    pub fn rekey(&mut self) {
+        self.state.rekey();
+    }
+}
+
+/// Receiving side of a secret stream.
+#[derive(Clone)]
+pub struct SecretStreamPull {
+    state: State,
+    finished: bool,
+}
+
+impl SecretStreamPull {
+    /// Starts reading a stream.
+    ///
+    /// # Arguments
+    /// * `key` - 32-byte stream key.
+    /// * `header` - The 24-byte header produced by the sender.
+    pub fn new(key: &[u8; KEY_BYTES], header: &[u8; HEADER_BYTES]) -> Self {
+        Self { state: State::new(key, header), finished: false }
+    }
+
+    /// Verifies and decrypts one chunk.
+    ///
+    /// The state only advances if the chunk authenticates; on error it is left unchanged.
+    ///
+    /// # Arguments
+    /// * `chunk` - Encrypted chunk, as produced by [`SecretStreamPush::push`].
+    /// * `ad` - Additional data that was authenticated with the chunk.
+    /// * `out` - Output buffer of at least `chunk.len() - ABYTES` bytes.
+    ///
+    /// # Returns
+    /// Plaintext length and the chunk tag.
+    ///
+    /// # Errors
+    /// - [`Error::StreamFinished`] if the final chunk was already received.
+    /// - [`Error::InvalidLength`] if `chunk` is shorter than `ABYTES` or `out` is too small.
+    /// - [`Error::AuthenticationFailed`] if the chunk is forged, corrupted or out of order.
+    pub fn pull(&mut self, chunk: &[u8], ad: &[u8], out: &mut [u8]) -> Result<(usize, Tag), Error> {
+        if self.finished {
+            return Err(Error::StreamFinished);
+        }
+        if chunk.len() < ABYTES {
+            return Err(Error::InvalidLength);
+        }
+        let len = chunk.len() - ABYTES;
+        if out.len() < len {
+            return Err(Error::InvalidLength);
+        }
+
+        let (mut cipher, mut mac) = self.state.begin(ad);
+
+        let mut block = [0u8; 64];
+        block[0] = chunk[0];
+        cipher.apply_keystream(&mut block);
+        let tag = block[0];
+        block[0] = chunk[0];
+        mac.update(&block);
+
+        let body = &chunk[1..1 + len];
+        let expected = State::finish_mac(mac, ad.len(), body);
+        if !verify_tag(&expected, chunk[1 + len..].try_into().unwrap()) {
+            return Err(Error::AuthenticationFailed);
+        }
+        let tag_value = Tag::from_byte(tag)?;
+
+        out[..len].copy_from_slice(body);
+        cipher.apply_keystream(&mut out[..len]);
+
+        self.state.advance(&expected, tag);
+        if tag_value == Tag::Final {
+            self.finished = true;
+        }
+        Ok((len, tag_value))
+    }
+
+    /// Forces a rekey, mirroring [`SecretStreamPush::rekey`] on the sending side.
+    pub fn rekey(&mut self) {
+        self.state.rekey();
+    }
+
+    /// Returns `true` once a [`Tag::Final`] chunk has been received.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Ends reading and checks that the stream was complete.
+    ///
+    /// # Errors
+    /// [`Error::Truncated`] if no [`Tag::Final`] chunk was received.
+    pub fn finish(self) -> Result<(), Error> {
+        if self.finished {
+            Ok(())
+        } else {
+            Err(Error::Truncated)
+        }
+    }
+}
@@ -0,0 +1,185 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Power-on self-test of the ChaCha20 backends, enabled by the `self_test` feature.
+//!
+//! The first [`FastChaCha20`](crate::FastChaCha20) or
+//! [`ChaChaBatch`](crate::batch::ChaChaBatch) created in the process runs known-answer tests
+//! (RFC 8439 Appendix A.1, test vector #2) on `fallback_chacha20::xor` and on the
+//! `ChaCha20_ctr32` path selected for this CPU, then checks that the multi-block paths of
+//! the latter agree with the former over 1 KiB. The result is cached: if any test fails,
+//! every later attempt to create a cipher panics instead of producing wrong ciphertext.
+//!
+//! Deployments that prefer to fail gracefully can call [`run`] at startup and check the
+//! returned [`Status`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::self_test::{self, Status};
+//!
+//! assert_eq!(self_test::run(), Status::Passed);
+//! assert_eq!(self_test::status(), Status::Passed);
+//! ```
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::{fallback_chacha20, init_cpu_caps, ChaCha20_ctr32};
+
+/// Backend whose self-test failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// The `ChaCha20_ctr32` dispatch path: the assembly where available, the Rust
+    /// implementation otherwise.
+    Ctr32,
+    /// The pure Rust block function in [`crate::fallback_chacha20`].
+    Fallback,
+}
+
+/// Outcome of the self-test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The self-test has not run yet.
+    NotRun,
+    /// Every test passed.
+    Passed,
+    /// A test on the given backend failed; ciphers can no longer be created.
+    Failed(Backend),
+}
+
+const NOT_RUN: u8 = 0;
+const PASSED: u8 = 1;
+const FAILED_CTR32: u8 = 2;
+const FAILED_FALLBACK: u8 = 3;
+
+/// Cached outcome, one of the constants above.
+static STATE: AtomicU8 = AtomicU8::new(NOT_RUN);
+
+/// RFC 8439 A.1 test vector #2: all-zero key and nonce, block counter 1.
+const KAT_COUNTER: [u32; 4] = [1, 0, 0, 0];
+const KAT_BLOCK: [u8; 64] = [
+    0x9f, 0x07, 0xe7, 0xbe, 0x55, 0x51, 0x38, 0x7a, 0x98, 0xba, 0x97, 0x7c, 0x73, 0x2d, 0x08, 0x0d,
+    0xcb, 0x0f, 0x29, 0xa0, 0x48, 0xe3, 0x65, 0x69, 0x12, 0xc6, 0x53, 0x3e, 0x32, 0xee, 0x7a, 0xed,
+    0x29, 0xb7, 0x21, 0x76, 0x9c, 0xe6, 0x4e, 0x43, 0xd5, 0x71, 0x33, 0xb0, 0x74, 0xd8, 0x39, 0xd5,
+    0x31, 0xed, 0x1f, 0x28, 0x51, 0x0a, 0xfb, 0x45, 0xac, 0xe1, 0x0a, 0x1f, 0x4b, 0x79, 0x4d, 0x6f,
+];
+
+/// Length of the consistency check, long enough for the widest asm path.
+const CONSISTENCY_LEN: usize = 1024;
+
+/// Signature shared by the backends under test: XOR the keystream at `counter` into `data`.
+type XorFn = fn(&mut [u8], &[u32; 8], &[u32; 4]);
+
+fn ctr32(data: &mut [u8], key: &[u32; 8], counter: &[u32; 4]) {
+    unsafe {
+        ChaCha20_ctr32(data.as_mut_ptr(), data.as_ptr(), data.len(), key.as_ptr(), counter.as_ptr())
+    };
+}
+
+fn fallback(data: &mut [u8], key: &[u32; 8], counter: &[u32; 4]) {
+    let mut counter = *counter;
+    for block in data.chunks_mut(64) {
+        fallback_chacha20::xor(block, false, key, &counter, 10);
+        counter[0] = counter[0].wrapping_add(1);
+    }
+}
+
+/// Runs the known-answer test on `xor`.
+fn known_answer(xor: XorFn) -> bool {
+    let mut block = [0u8; 64];
+    xor(&mut block, &[0; 8], &KAT_COUNTER);
+    block == KAT_BLOCK
+}
+
+/// Checks that `xor` matches `reference` over [`CONSISTENCY_LEN`] bytes.
+fn consistent(xor: XorFn, reference: XorFn) -> bool {
+    let key = core::array::from_fn(|i| 0x0101_0101 * i as u32);
+    let counter = [7, 0x0900_0000, 0x4a00_0000, 0];
+    let mut a = [0u8; CONSISTENCY_LEN];
+    let mut b = [0u8; CONSISTENCY_LEN];
+    xor(&mut a, &key, &counter);
+    reference(&mut b, &key, &counter);
+    a == b
+}
+
+/// Tests the reference backend first, since the consistency check relies on it.
+fn evaluate(ctr32: XorFn, fallback: XorFn) -> u8 {
+    if !known_answer(fallback) {
+        FAILED_FALLBACK
+    } else if !known_answer(ctr32) || !consistent(ctr32, fallback) {
+        FAILED_CTR32
+    } else {
+        PASSED
+    }
+}
+
+fn decode(state: u8) -> Status {
+    match state {
+        NOT_RUN => Status::NotRun,
+        PASSED => Status::Passed,
+        FAILED_CTR32 => Status::Failed(Backend::Ctr32),
+        _ => Status::Failed(Backend::Fallback),
+    }
+}
+
+/// Returns the cached self-test outcome without running it.
+pub fn status() -> Status {
+    decode(STATE.load(Ordering::Acquire))
+}
+
+/// Runs the self-test unless it has already run, and returns its outcome.
+///
+/// The tests take a few microseconds; concurrent first calls may each run them, with the
+/// same result.
+pub fn run() -> Status {
+    let state = STATE.load(Ordering::Acquire);
+    if state != NOT_RUN {
+        return decode(state);
+    }
+    init_cpu_caps();
+    let state = evaluate(ctr32, fallback);
+    STATE.store(state, Ordering::Release);
+    decode(state)
+}
+
+/// Runs the self-test if needed and refuses to continue if it failed.
+///
+/// # Panics
+/// Panics if a backend failed its self-test.
+pub(crate) fn require() {
+    if let Status::Failed(backend) = run() {
+        panic!("fast_chacha: {backend:?} backend failed its power-on self-test");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn broken(data: &mut [u8], key: &[u32; 8], counter: &[u32; 4]) {
+        fallback(data, key, counter);
+        if let Some(byte) = data.get_mut(700) {
+            *byte ^= 1;
+        }
+    }
+
+    #[test]
+    fn failures_are_attributed() {
+        assert_eq!(evaluate(ctr32, fallback), PASSED);
+        assert_eq!(evaluate(broken, fallback), FAILED_CTR32);
+        assert_eq!(evaluate(ctr32, |data, _, _| data.fill(0)), FAILED_FALLBACK);
+    }
+}
@@ -0,0 +1,129 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! WebAssembly SIMD (simd128) ChaCha backend.
+//!
+//! Compiled when building for `wasm32` with `-C target-feature=+simd128`. Four consecutive
+//! blocks are computed at once, one per 32-bit lane, and transposed back into block order
+//! before they are combined with the data. The rotations by 16 and 8 are byte shuffles.
+
+use core::arch::wasm32::*;
+
+/// Size of the four blocks processed per call, in bytes.
+pub(crate) const CHUNK_BYTES: usize = 256;
+
+#[inline(always)]
+fn rotl(x: v128, n: u32) -> v128 {
+    v128_or(u32x4_shl(x, n), u32x4_shr(x, 32 - n))
+}
+
+#[inline(always)]
+fn rotl16(x: v128) -> v128 {
+    i8x16_shuffle::<2, 3, 0, 1, 6, 7, 4, 5, 10, 11, 8, 9, 14, 15, 12, 13>(x, x)
+}
+
+#[inline(always)]
+fn rotl8(x: v128) -> v128 {
+    i8x16_shuffle::<3, 0, 1, 2, 7, 4, 5, 6, 11, 8, 9, 10, 15, 12, 13, 14>(x, x)
+}
+
+#[inline(always)]
+fn quarter_round(s: &mut [v128; 16], a: usize, b: usize, c: usize, d: usize) {
+    s[a] = u32x4_add(s[a], s[b]);
+    s[d] = rotl16(v128_xor(s[d], s[a]));
+    s[c] = u32x4_add(s[c], s[d]);
+    s[b] = rotl(v128_xor(s[b], s[c]), 12);
+    s[a] = u32x4_add(s[a], s[b]);
+    s[d] = rotl8(v128_xor(s[d], s[a]));
+    s[c] = u32x4_add(s[c], s[d]);
+    s[b] = rotl(v128_xor(s[b], s[c]), 7);
+}
+
+/// XORs `data` with keystream blocks `counter[0]..counter[0] + 4` (or, with
+/// `keystream_only`, overwrites it with them).
+///
+/// The block counter wraps within the 32-bit word, like the scalar fallback.
+///
+/// # Arguments
+/// * `data` - Exactly [`CHUNK_BYTES`] bytes.
+/// * `keystream_only` - Write the keystream instead of XORing it.
+/// * `key` - 256-bit key as 8 u32 words.
+/// * `counter` - Block counter of the first block, and nonce words.
+/// * `double_rounds` - Number of double rounds (standard is 10).
+pub(crate) fn xor4(
+    data: &mut [u8],
+    keystream_only: bool,
+    key: &[u32; 8],
+    counter: &[u32; 4],
+    double_rounds: usize,
+) {
+    assert_eq!(data.len(), CHUNK_BYTES);
+
+    let mut state = [u32x4_splat(0); 16];
+    state[0] = u32x4_splat(0x61707865);
+    state[1] = u32x4_splat(0x3320646e);
+    state[2] = u32x4_splat(0x79622d32);
+    state[3] = u32x4_splat(0x6b206574);
+    for (i, word) in key.iter().enumerate() {
+        state[4 + i] = u32x4_splat(*word);
+    }
+    state[12] = u32x4_add(u32x4_splat(counter[0]), u32x4(0, 1, 2, 3));
+    for i in 1..4 {
+        state[12 + i] = u32x4_splat(counter[i]);
+    }
+
+    let mut working = state;
+    for _ in 0..double_rounds {
+        // Column rounds
+        quarter_round(&mut working, 0, 4, 8, 12);
+        quarter_round(&mut working, 1, 5, 9, 13);
+        quarter_round(&mut working, 2, 6, 10, 14);
+        quarter_round(&mut working, 3, 7, 11, 15);
+        // Diagonal rounds
+        quarter_round(&mut working, 0, 5, 10, 15);
+        quarter_round(&mut working, 1, 6, 11, 12);
+        quarter_round(&mut working, 2, 7, 8, 13);
+        quarter_round(&mut working, 3, 4, 9, 14);
+    }
+    for (w, s) in working.iter_mut().zip(state.iter()) {
+        *w = u32x4_add(*w, *s);
+    }
+
+    // Transpose each group of four words, so row `lane` holds 16 bytes of block `lane`.
+    for group in 0..4 {
+        let [a, b, c, d] = [0, 1, 2, 3].map(|i| working[group * 4 + i]);
+        let ab_lo = i32x4_shuffle::<0, 4, 1, 5>(a, b);
+        let ab_hi = i32x4_shuffle::<2, 6, 3, 7>(a, b);
+        let cd_lo = i32x4_shuffle::<0, 4, 1, 5>(c, d);
+        let cd_hi = i32x4_shuffle::<2, 6, 3, 7>(c, d);
+        let rows = [
+            i64x2_shuffle::<0, 2>(ab_lo, cd_lo),
+            i64x2_shuffle::<1, 3>(ab_lo, cd_lo),
+            i64x2_shuffle::<0, 2>(ab_hi, cd_hi),
+            i64x2_shuffle::<1, 3>(ab_hi, cd_hi),
+        ];
+
+        for (lane, row) in rows.into_iter().enumerate() {
+            let bytes = &mut data[lane * 64 + group * 16..][..16];
+            let ptr = bytes.as_mut_ptr() as *mut v128;
+            // SAFETY: `bytes` is 16 bytes long; `v128_load`/`v128_store` are unaligned.
+            unsafe {
+                let out = if keystream_only { row } else { v128_xor(v128_load(ptr), row) };
+                v128_store(ptr, out);
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The `chacha20-poly1305@openssh.com` SSH transport cipher.
+//!
+//! As specified in OpenSSH's `PROTOCOL.chacha20poly1305`:
+//!
+//! - the 64-byte key is split into `K_2` (first half, payload) and `K_1` (second half,
+//!   packet length);
+//! - ChaCha20 uses the original 64-bit counter / 64-bit nonce layout, with the packet
+//!   sequence number as a big-endian nonce. In the 96-bit layout of [`FastChaCha20`] this is
+//!   the nonce `0u32 || seqnr.to_be_bytes()`;
+//! - the 4-byte packet length is encrypted with `K_1` from block 0;
+//! - the Poly1305 key is the first 32 bytes of `K_2` block 0, and the payload is encrypted
+//!   with `K_2` starting at block 1;
+//! - the tag covers the encrypted length followed by the encrypted payload.
+//!
+//! Packets are processed in place, laid out as `length (4) || payload || tag (16)`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::ssh::{SshChaCha20Poly1305, LENGTH_BYTES, TAG_BYTES};
+//!
+//! let cipher = SshChaCha20Poly1305::new(&[9u8; 64]);
+//! let payload = b"\x05\x00\x00\x00\x0cssh-userauth";
+//!
+//! let mut packet = Vec::new();
+//! packet.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+//! packet.extend_from_slice(payload);
+//! packet.extend_from_slice(&[0u8; TAG_BYTES]);
+//! cipher.encrypt_packet(3, &mut packet).unwrap();
+//!
+//! let len = cipher.decrypt_length(3, packet[..LENGTH_BYTES].try_into().unwrap());
+//! assert_eq!(len as usize, payload.len());
+//! cipher.decrypt_packet(3, &mut packet).unwrap();
+//! assert_eq!(&packet[LENGTH_BYTES..LENGTH_BYTES + payload.len()], payload);
+//! ```
+
+use crate::poly1305::{verify_tag, Poly1305};
+use crate::{Error, FastChaCha20};
+
+/// Size of the combined key (`K_2 || K_1`), in bytes.
+pub const KEY_BYTES: usize = 64;
+/// Size of the encrypted packet length field, in bytes.
+pub const LENGTH_BYTES: usize = 4;
+/// Size of the Poly1305 tag, in bytes.
+pub const TAG_BYTES: usize = 16;
+
+/// Builds the 96-bit nonce for a sequence number (zero high counter word, big-endian seqnr).
+#[inline(always)]
+fn nonce(seq: u32) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[4..].copy_from_slice(&(seq as u64).to_be_bytes());
+    nonce
+}
+
+/// `chacha20-poly1305@openssh.com` packet cipher for one direction of a connection.
+#[derive(Clone)]
+pub struct SshChaCha20Poly1305 {
+    /// `K_2`: payload encryption and Poly1305 key derivation.
+    main_key: [u8; 32],
+    /// `K_1`: packet length encryption.
+    header_key: [u8; 32],
+}
+
+impl SshChaCha20Poly1305 {
+    /// Creates the cipher from the 64 bytes of key material negotiated by the key exchange.
+    ///
+    /// # Arguments
+    /// * `key` - `K_2 || K_1`, as derived by SSH key exchange.
+    pub fn new(key: &[u8; KEY_BYTES]) -> Self {
+        Self { main_key: key[..32].try_into().unwrap(), header_key: key[32..].try_into().unwrap() }
+    }
+
+    /// Returns the payload cipher positioned at block 1 and the packet's Poly1305 state.
+    fn payload_cipher(&self, seq: u32) -> (FastChaCha20, Poly1305) {
+        let mut cipher = FastChaCha20::new(&self.main_key, &nonce(seq));
+        let mut poly_key = [0u8; 32];
+        cipher.apply_keystream(&mut poly_key);
+        cipher.set_counter(1);
+        (cipher, Poly1305::new(&poly_key))
+    }
+
+    /// Encrypts a packet in place and appends the tag.
+    ///
+    /// # Arguments
+    /// * `seq` - Packet sequence number.
+    /// * `packet` - `length (4) || payload || tag space (16)`; the last 16 bytes are
+    ///   overwritten with the tag.
+    ///
+    /// # Errors
+    /// [`Error::InvalidLength`] if `packet` is shorter than `LENGTH_BYTES + TAG_BYTES`.
+    pub fn encrypt_packet(&self, seq: u32, packet: &mut [u8]) -> Result<(), Error> {
+        if packet.len() < LENGTH_BYTES + TAG_BYTES {
+            return Err(Error::InvalidLength);
+        }
+        let (body, tag) = packet.split_at_mut(packet.len() - TAG_BYTES);
+        let (length, payload) = body.split_at_mut(LENGTH_BYTES);
+
+        FastChaCha20::new(&self.header_key, &nonce(seq)).apply_keystream(length);
+
+        let (mut cipher, mut mac) = self.payload_cipher(seq);
+        cipher.apply_keystream(payload);
+
+        mac.update(body);
+        tag.copy_from_slice(&mac.finalize());
+        Ok(())
+    }
+
+    /// Decrypts the packet length field, so the caller knows how much more to read.
+    ///
+    /// The length is not authenticated until [`SshChaCha20Poly1305::decrypt_packet`]
+    /// succeeds; callers should bound it before allocating.
+    ///
+    /// # Arguments
+    /// * `seq` - Packet sequence number.
+    /// * `encrypted` - The first 4 bytes of the packet as received.
+    pub fn decrypt_length(&self, seq: u32, encrypted: &[u8; LENGTH_BYTES]) -> u32 {
+        let mut length = *encrypted;
+        FastChaCha20::new(&self.header_key, &nonce(seq)).apply_keystream(&mut length);
+        u32::from_be_bytes(length)
+    }
+
+    /// Verifies the tag and decrypts a packet in place.
+    ///
+    /// On success, the length field and the payload are replaced with their plaintext;
+    /// on failure the packet is left untouched.
+    ///
+    /// # Arguments
+    /// * `seq` - Packet sequence number.
+    /// * `packet` - `length (4) || payload || tag (16)` as received.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidLength`] if `packet` is shorter than `LENGTH_BYTES + TAG_BYTES`.
+    /// - [`Error::AuthenticationFailed`] if the tag does not verify.
+    pub fn decrypt_packet(&self, seq: u32, packet: &mut [u8]) -> Result<(), Error> {
+        if packet.len() < LENGTH_BYTES + TAG_BYTES {
+            return Err(Error::InvalidLength);
+        }
+        let (body, tag) = packet.split_at_mut(packet.len() - TAG_BYTES);
+
+        let (mut cipher, mut mac) = self.payload_cipher(seq);
+        mac.update(body);
+        if !verify_tag(&mac.finalize(), (&*tag).try_into().unwrap()) {
+            return Err(Error::AuthenticationFailed);
+        }
+
+        let (length, payload) = body.split_at_mut(LENGTH_BYTES);
+        FastChaCha20::new(&self.header_key, &nonce(seq)).apply_keystream(length);
+        cipher.apply_keystream(payload);
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! TLS 1.3 and DTLS 1.3 record protection with `TLS_CHACHA20_POLY1305_SHA256`
+//! (RFC 8446 §5.2–5.4, RFC 9147 §4).
+//!
+//! [`RecordProtection`] holds the traffic key and static IV of one direction and the
+//! 64-bit record sequence number. For every record:
+//!
+//! - the nonce is the IV with the big-endian sequence number XORed into its last 8 bytes;
+//! - the plaintext is wrapped as `TLSInnerPlaintext` (`content || content_type || zeros`);
+//! - the 5-byte record header (`application_data`, `0x0303`, length) is the AAD.
+//!
+//! [`DtlsRecordProtection`] does the same for one DTLS 1.3 epoch, with the differences
+//! datagrams need:
+//!
+//! - records carry the unified header (`0b001CSLEE`, truncated sequence number, optional
+//!   length) instead of the TLS one, and that header, with the sequence number in the
+//!   clear, is the AAD;
+//! - the truncated sequence number is encrypted with a mask derived from the first 16
+//!   bytes of ciphertext and the `sn_key` (record number encryption, RFC 9147 §4.2.3);
+//! - the receiver rebuilds the full sequence number from the 8 or 16 bits on the wire,
+//!   and records that fail to authenticate are dropped without consuming it.
+//!
+//! Key derivation (HKDF), the handshake, connection IDs, replay windows and ACKs are out of
+//! scope.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::tls::{RecordProtection, APPLICATION_DATA, HEADER_BYTES, TAG_BYTES};
+//!
+//! let key = [4u8; 32];
+//! let iv = [6u8; 12];
+//! let mut client = RecordProtection::new(&key, &iv);
+//! let mut server = RecordProtection::new(&key, &iv);
+//!
+//! let mut record = [0u8; HEADER_BYTES + 5 + 1 + 10 + TAG_BYTES];
+//! let n = client.seal_record(APPLICATION_DATA, b"hello", 10, &mut record).unwrap();
+//! assert_eq!(n, record.len());
+//!
+//! let (content_type, content) = server.open_record(&mut record).unwrap();
+//! assert_eq!((content_type, content), (APPLICATION_DATA, &b"hello"[..]));
+//! ```
+
+use crate::aead::ChaCha20Poly1305;
+pub use crate::aead::{KEY_BYTES, NONCE_BYTES as IV_BYTES, TAG_BYTES};
+use crate::quic::{quic_header_mask, MASK_BYTES, SAMPLE_BYTES};
+use crate::Error;
+
+/// `change_cipher_spec` content type.
+pub const CHANGE_CIPHER_SPEC: u8 = 20;
+/// `alert` content type.
+pub const ALERT: u8 = 21;
+/// `handshake` content type.
+pub const HANDSHAKE: u8 = 22;
+/// `application_data` content type, also the outer type of every protected record.
+pub const APPLICATION_DATA: u8 = 23;
+
+/// Size of the record header, in bytes.
+pub const HEADER_BYTES: usize = 5;
+/// Largest `TLSInnerPlaintext` (content, content type and padding), in bytes.
+pub const MAX_INNER_PLAINTEXT: usize = (1 << 14) + 1;
+/// Largest encrypted record body (`TLSInnerPlaintext` plus expansion), in bytes.
+pub const MAX_CIPHERTEXT: usize = (1 << 14) + 256;
+
+/// `legacy_record_version` of every TLS 1.3 protected record.
+const LEGACY_VERSION: [u8; 2] = [0x03, 0x03];
+
+/// Builds the per-record nonce: the IV with the sequence number XORed into its last 8 bytes.
+fn record_nonce(iv: &[u8; IV_BYTES], seq: u64) -> [u8; IV_BYTES] {
+    let mut nonce = *iv;
+    for (n, s) in nonce[4..].iter_mut().zip(seq.to_be_bytes()) {
+        *n ^= s;
+    }
+    nonce
+}
+
+/// Returns the length of the `TLSInnerPlaintext` of `content` with `padding` zeros.
+fn inner_len(content: &[u8], padding: usize) -> Result<usize, Error> {
+    content
+        .len()
+        .checked_add(1)
+        .and_then(|n| n.checked_add(padding))
+        .filter(|&n| n <= MAX_INNER_PLAINTEXT)
+        .ok_or(Error::InvalidLength)
+}
+
+/// Writes `content || content_type || zeros` into `inner`.
+fn write_inner(inner: &mut [u8], content_type: u8, content: &[u8]) {
+    inner[..content.len()].copy_from_slice(content);
+    inner[content.len()] = content_type;
+    inner[content.len() + 1..].fill(0);
+}
+
+/// Splits a decrypted `TLSInnerPlaintext` into its content type and content.
+fn strip_padding(inner: &[u8]) -> Result<(u8, &[u8]), Error> {
+    // The content type is the last non-zero byte; everything after it is padding.
+    let end = inner.iter().rposition(|&b| b != 0).ok_or(Error::InvalidPadding)?;
+    Ok((inner[end], &inner[..end]))
+}
+
+/// One direction of TLS 1.3 record protection.
+///
+/// The sequence number starts at 0 and is incremented by every sealed or opened record,
+/// including one that fails to authenticate (TLS treats that as fatal anyway). Once it
+/// reaches `2^64 - 1` the key must be updated; all further operations return
+/// [`Error::NonceExhausted`].
+#[derive(Clone)]
+pub struct RecordProtection {
+    aead: ChaCha20Poly1305,
+    iv: [u8; IV_BYTES],
+    seq: u64,
+}
+
+impl RecordProtection {
+    /// Creates record protection from a traffic key and static IV.
+    ///
+    /// # Arguments
+    /// * `key` - `[sender]_write_key`.
+    /// * `iv` - `[sender]_write_iv`.
+    pub fn new(key: &[u8; KEY_BYTES], iv: &[u8; IV_BYTES]) -> Self {
+        Self { aead: ChaCha20Poly1305::new(key), iv: *iv, seq: 0 }
+    }
+
+    /// Returns the sequence number of the next record.
+    pub fn sequence_number(&self) -> u64 {
+        self.seq
+    }
+
+    /// Sets the sequence number of the next record.
+    ///
+    /// Only needed when resuming protection state; reusing a sequence number with the same
+    /// key reuses the nonce and breaks confidentiality and integrity.
+    pub fn set_sequence_number(&mut self, seq: u64) {
+        self.seq = seq;
+    }
+
+    /// Builds the per-record nonce and consumes the sequence number.
+    fn next_nonce(&mut self) -> Result<[u8; IV_BYTES], Error> {
+        if self.seq == u64::MAX {
+            return Err(Error::NonceExhausted);
+        }
+        let nonce = record_nonce(&self.iv, self.seq);
+        self.seq += 1;
+        Ok(nonce)
+    }
+
+    /// Encrypts one record into `out` and returns the record length.
+    ///
+    /// # Arguments
+    /// * `content_type` - Real content type, e.g. [`HANDSHAKE`] or [`APPLICATION_DATA`].
+    /// * `content` - Record content.
+    /// * `padding` - Number of zero bytes appended to hide the content length.
+    /// * `out` - Output buffer of at least
+    ///   `HEADER_BYTES + content.len() + 1 + padding + TAG_BYTES` bytes.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidLength`] if the inner plaintext exceeds [`MAX_INNER_PLAINTEXT`] or
+    ///   `out` is too small.
+    /// - [`Error::NonceExhausted`] if the sequence number space is used up.
+    pub fn seal_record(
+        &mut self,
+        content_type: u8,
+        content: &[u8],
+        padding: usize,
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        let inner_len = inner_len(content, padding)?;
+        if out.len() < HEADER_BYTES + inner_len + TAG_BYTES {
+            return Err(Error::InvalidLength);
+        }
+        let nonce = self.next_nonce()?;
+
+        let (header, body) = out.split_at_mut(HEADER_BYTES);
+        header[0] = APPLICATION_DATA;
+        header[1..3].copy_from_slice(&LEGACY_VERSION);
+        header[3..5].copy_from_slice(&((inner_len + TAG_BYTES) as u16).to_be_bytes());
+
+        let (inner, rest) = body.split_at_mut(inner_len);
+        write_inner(inner, content_type, content);
+
+        let tag = self.aead.encrypt_in_place_detached(&nonce, header, inner)?;
+        rest[..TAG_BYTES].copy_from_slice(&tag);
+
+        Ok(HEADER_BYTES + inner_len + TAG_BYTES)
+    }
+
+    /// Decrypts one record in place and returns its real content type and content.
+    ///
+    /// # Arguments
+    /// * `record` - One complete record, header included, exactly as received.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidLength`] if the record is shorter than its header and tag, longer
+    ///   than [`MAX_CIPHERTEXT`] allows, or its length field does not match.
+    /// - [`Error::NonceExhausted`] if the sequence number space is used up.
+    /// - [`Error::AuthenticationFailed`] if the tag does not verify.
+    /// - [`Error::InvalidPadding`] if the decrypted record holds no non-zero content type.
+    pub fn open_record<'a>(&mut self, record: &'a mut [u8]) -> Result<(u8, &'a [u8]), Error> {
+        if record.len() < HEADER_BYTES + TAG_BYTES + 1
+            || record.len() > HEADER_BYTES + MAX_CIPHERTEXT
+            || u16::from_be_bytes([record[3], record[4]]) as usize != record.len() - HEADER_BYTES
+        {
+            return Err(Error::InvalidLength);
+        }
+        let nonce = self.next_nonce()?;
+
+        let (header, body) = record.split_at_mut(HEADER_BYTES);
+        let (inner, tag) = body.split_at_mut(body.len() - TAG_BYTES);
+        let tag: &[u8; TAG_BYTES] = (&*tag).try_into().unwrap();
+        self.aead.decrypt_in_place_detached(&nonce, header, inner, tag)?;
+
+        strip_padding(inner)
+    }
+}
+
+/// Size of the DTLS 1.3 unified header written by [`DtlsRecordProtection::seal_record`]:
+/// flags, 16-bit sequence number and length.
+pub const DTLS_HEADER_BYTES: usize = 5;
+
+/// Fixed bits of the unified header's first byte (`0b001xxxxx`).
+const DTLS_FIXED_BITS: u8 = 0b0010_0000;
+/// Connection ID present.
+const DTLS_C: u8 = 0b0001_0000;
+/// 16-bit (instead of 8-bit) sequence number.
+const DTLS_S: u8 = 0b0000_1000;
+/// Length field present.
+const DTLS_L: u8 = 0b0000_0100;
+/// Low two bits of the epoch.
+const DTLS_EPOCH_BITS: u8 = 0b0000_0011;
+
+/// Parses the first byte of a unified header and returns the header length and the length
+/// of its sequence number field.
+fn dtls_header_layout(flags: u8) -> Result<(usize, usize), Error> {
+    if flags & !(DTLS_C | DTLS_S | DTLS_L | DTLS_EPOCH_BITS) != DTLS_FIXED_BITS
+        || flags & DTLS_C != 0
+    {
+        return Err(Error::InvalidHeader);
+    }
+    let seq_len = if flags & DTLS_S != 0 { 2 } else { 1 };
+    let length_len = if flags & DTLS_L != 0 { 2 } else { 0 };
+    Ok((1 + seq_len + length_len, seq_len))
+}
+
+/// Returns the length of the DTLS 1.3 record at the start of `datagram`, so that datagrams
+/// holding several records can be split before opening them.
+///
+/// A record without a length field extends to the end of the datagram.
+///
+/// # Arguments
+/// * `datagram` - Received datagram, starting with a unified header.
+///
+/// # Errors
+/// - [`Error::InvalidHeader`] if the first byte is not a unified header without a
+///   connection ID.
+/// - [`Error::InvalidLength`] if the header or the record is cut short.
+pub fn dtls_record_len(datagram: &[u8]) -> Result<usize, Error> {
+    let (header_len, seq_len) = dtls_header_layout(*datagram.first().ok_or(Error::InvalidLength)?)?;
+    if datagram.len() < header_len {
+        return Err(Error::InvalidLength);
+    }
+    if header_len == 1 + seq_len {
+        return Ok(datagram.len());
+    }
+    let length = u16::from_be_bytes([datagram[1 + seq_len], datagram[2 + seq_len]]) as usize;
+    if datagram.len() < header_len + length {
+        return Err(Error::InvalidLength);
+    }
+    Ok(header_len + length)
+}
+
+/// Recovers a full sequence number from its low `bits` bits: the candidate closest to
+/// `expected` (RFC 9147 §4.2.2).
+fn decode_sequence_number(expected: u64, truncated: u64, bits: u32) -> u64 {
+    let win = 1u64 << bits;
+    let candidate = (expected & !(win - 1)) | truncated;
+    if candidate < expected && expected - candidate > win / 2 {
+        candidate.checked_add(win).unwrap_or(candidate)
+    } else if candidate > expected && candidate - expected > win / 2 && candidate >= win {
+        candidate - win
+    } else {
+        candidate
+    }
+}
+
+/// One epoch and direction of DTLS 1.3 record protection.
+///
+/// Sealing numbers records 0, 1, 2, ... within the epoch and returns
+/// [`Error::NonceExhausted`] once `2^64 - 1` is reached. Opening rebuilds each record's
+/// sequence number from the truncated one on the wire, relative to the highest record
+/// opened so far; records that fail to authenticate are left as received and do not
+/// move it, as DTLS drops them silently. Replay protection is up to the caller, using the
+/// sequence number [`DtlsRecordProtection::open_record`] returns.
+#[derive(Clone)]
+pub struct DtlsRecordProtection {
+    aead: ChaCha20Poly1305,
+    iv: [u8; IV_BYTES],
+    sn_key: [u8; KEY_BYTES],
+    epoch: u64,
+    /// Next sequence number to seal, or one past the highest opened.
+    seq: u64,
+}
+
+impl DtlsRecordProtection {
+    /// Creates record protection for one epoch from its traffic key, static IV and
+    /// sequence number key.
+    ///
+    /// # Arguments
+    /// * `key` - `[sender]_write_key` of the epoch.
+    /// * `iv` - `[sender]_write_iv` of the epoch.
+    /// * `sn_key` - `[sender]_sn_key` of the epoch.
+    /// * `epoch` - Epoch number; its low two bits go into every header.
+    pub fn new(
+        key: &[u8; KEY_BYTES],
+        iv: &[u8; IV_BYTES],
+        sn_key: &[u8; KEY_BYTES],
+        epoch: u64,
+    ) -> Self {
+        Self { aead: ChaCha20Poly1305::new(key), iv: *iv, sn_key: *sn_key, epoch, seq: 0 }
+    }
+
+    /// Returns the epoch.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Returns the sequence number of the next sealed record, or one past the highest
+    /// opened one.
+    pub fn sequence_number(&self) -> u64 {
+        self.seq
+    }
+
+    /// Sets the sequence number of the next record.
+    ///
+    /// Only needed when resuming protection state; reusing a sequence number with the same
+    /// key reuses the nonce and breaks confidentiality and integrity.
+    pub fn set_sequence_number(&mut self, seq: u64) {
+        self.seq = seq;
+    }
+
+    /// Computes the record number mask from the first 16 bytes of ciphertext.
+    fn mask(&self, ciphertext: &[u8]) -> [u8; MASK_BYTES] {
+        quic_header_mask(&self.sn_key, ciphertext[..SAMPLE_BYTES].try_into().unwrap())
+    }
+
+    /// Encrypts one record into `out` and returns the record length.
+    ///
+    /// The record has a [`DTLS_HEADER_BYTES`]-byte unified header with a 16-bit sequence
+    /// number and a length field, so several records can share a datagram.
+    ///
+    /// # Arguments
+    /// * `content_type` - Real content type, e.g. [`HANDSHAKE`] or [`APPLICATION_DATA`].
+    /// * `content` - Record content.
+    /// * `padding` - Number of zero bytes appended to hide the content length.
+    /// * `out` - Output buffer of at least
+    ///   `DTLS_HEADER_BYTES + content.len() + 1 + padding + TAG_BYTES` bytes.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidLength`] if the inner plaintext exceeds [`MAX_INNER_PLAINTEXT`] or
+    ///   `out` is too small.
+    /// - [`Error::NonceExhausted`] if the sequence number space is used up.
+    pub fn seal_record(
+        &mut self,
+        content_type: u8,
+        content: &[u8],
+        padding: usize,
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        let inner_len = inner_len(content, padding)?;
+        if out.len() < DTLS_HEADER_BYTES + inner_len + TAG_BYTES {
+            return Err(Error::InvalidLength);
+        }
+        if self.seq == u64::MAX {
+            return Err(Error::NonceExhausted);
+        }
+        let seq = self.seq;
+
+        let (header, body) = out.split_at_mut(DTLS_HEADER_BYTES);
+        header[0] = DTLS_FIXED_BITS | DTLS_S | DTLS_L | (self.epoch as u8 & DTLS_EPOCH_BITS);
+        header[1..3].copy_from_slice(&(seq as u16).to_be_bytes());
+        header[3..5].copy_from_slice(&((inner_len + TAG_BYTES) as u16).to_be_bytes());
+
+        let (inner, rest) = body.split_at_mut(inner_len);
+        write_inner(inner, content_type, content);
+        let nonce = record_nonce(&self.iv, seq);
+        let tag = self.aead.encrypt_in_place_detached(&nonce, header, inner)?;
+        rest[..TAG_BYTES].copy_from_slice(&tag);
+        self.seq += 1;
+
+        let mask = self.mask(&body[..inner_len + TAG_BYTES]);
+        for (b, m) in header[1..3].iter_mut().zip(mask) {
+            *b ^= m;
+        }
+        Ok(DTLS_HEADER_BYTES + inner_len + TAG_BYTES)
+    }
+
+    /// Decrypts one record in place and returns its real content type, its content and its
+    /// full sequence number.
+    ///
+    /// Any unified header without a connection ID is accepted: 8- or 16-bit sequence
+    /// number, with or without a length field. Use [`dtls_record_len`] to split a datagram
+    /// holding several records.
+    ///
+    /// # Arguments
+    /// * `record` - One complete record, header included, exactly as received.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidHeader`] if the header is not a unified header, carries a
+    ///   connection ID, or belongs to another epoch.
+    /// - [`Error::InvalidLength`] if the record is shorter than its header and tag, longer
+    ///   than [`MAX_CIPHERTEXT`] allows, or its length field does not match.
+    /// - [`Error::AuthenticationFailed`] if the tag does not verify.
+    /// - [`Error::InvalidPadding`] if the decrypted record holds no non-zero content type.
+    pub fn open_record<'a>(&mut self, record: &'a mut [u8]) -> Result<(u8, &'a [u8], u64), Error> {
+        let flags = *record.first().ok_or(Error::InvalidLength)?;
+        let (header_len, seq_len) = dtls_header_layout(flags)?;
+        if u64::from(flags & DTLS_EPOCH_BITS) != self.epoch & u64::from(DTLS_EPOCH_BITS) {
+            return Err(Error::InvalidHeader);
+        }
+        if record.len() < header_len + TAG_BYTES + 1
+            || record.len() > header_len + MAX_CIPHERTEXT
+            || dtls_record_len(record)? != record.len()
+        {
+            return Err(Error::InvalidLength);
+        }
+
+        let (header, body) = record.split_at_mut(header_len);
+        let mask = self.mask(body);
+        let unmask = |header: &mut [u8]| {
+            for (b, m) in header[1..1 + seq_len].iter_mut().zip(mask) {
+                *b ^= m;
+            }
+        };
+        unmask(header);
+        let truncated = header[1..1 + seq_len].iter().fold(0u64, |acc, &b| acc << 8 | b as u64);
+        let seq = decode_sequence_number(self.seq, truncated, 8 * seq_len as u32);
+
+        let (inner, tag) = body.split_at_mut(body.len() - TAG_BYTES);
+        let tag: &[u8; TAG_BYTES] = (&*tag).try_into().unwrap();
+        let nonce = record_nonce(&self.iv, seq);
+        if let Err(e) = self.aead.decrypt_in_place_detached(&nonce, header, inner, tag) {
+            unmask(header);
+            return Err(e);
+        }
+        self.seq = self.seq.max(seq.saturating_add(1));
+
+        let inner: &'a [u8] = inner;
+        let (content_type, content) = strip_padding(inner)?;
+        Ok((content_type, content, seq))
+    }
+}
@@ -0,0 +1,183 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! JavaScript bindings, generated with wasm-bindgen.
+//!
+//! Enabled with the `wasm` feature. Exposes the stream cipher as `ChaCha20` and the AEADs
+//! as `ChaCha20Poly1305` and `XChaCha20Poly1305`, whose `encrypt` returns the ciphertext
+//! followed by the 16-byte tag and whose `decrypt` takes the same layout. Invalid lengths
+//! and failed authentication throw an `Error`. Build for `wasm32-unknown-unknown` with
+//! `simd128` enabled (the default in `.cargo/config.toml`) to get the SIMD backend.
+//!
+//! # Example
+//!
+//! ```js
+//! import { ChaCha20Poly1305 } from "./fast_chacha.js";
+//!
+//! const aead = new ChaCha20Poly1305(key);
+//! const sealed = aead.encrypt(nonce, new TextEncoder().encode("hello"), new Uint8Array());
+//! const opened = aead.decrypt(nonce, sealed, new Uint8Array());
+//! ```
+
+use std::vec::Vec;
+
+use wasm_bindgen::prelude::*;
+
+use crate::aead::{self, KEY_BYTES, NONCE_BYTES, TAG_BYTES, X_NONCE_BYTES};
+use crate::{Error, FastChaCha20};
+
+/// Borrows `bytes` as an array of exactly `N` bytes.
+fn array<const N: usize>(bytes: &[u8]) -> Result<&[u8; N], JsError> {
+    bytes.try_into().map_err(|_| Error::InvalidLength.into())
+}
+
+/// Copies `plaintext`, encrypts it with `seal` and appends the tag.
+fn encrypt(
+    plaintext: &[u8],
+    seal: impl FnOnce(&mut [u8]) -> Result<[u8; TAG_BYTES], Error>,
+) -> Result<Vec<u8>, JsError> {
+    let mut out = Vec::with_capacity(plaintext.len() + TAG_BYTES);
+    out.extend_from_slice(plaintext);
+    let tag = seal(&mut out)?;
+    out.extend_from_slice(&tag);
+    Ok(out)
+}
+
+/// Splits off the tag, copies the ciphertext and decrypts it with `open`.
+fn decrypt(
+    sealed: &[u8],
+    open: impl FnOnce(&mut [u8], &[u8; TAG_BYTES]) -> Result<(), Error>,
+) -> Result<Vec<u8>, JsError> {
+    let split = sealed.len().checked_sub(TAG_BYTES).ok_or(Error::InvalidLength)?;
+    let (ciphertext, tag) = sealed.split_at(split);
+    let mut out = ciphertext.to_vec();
+    open(&mut out, array(tag)?)?;
+    Ok(out)
+}
+
+/// ChaCha20 stream cipher (RFC 8439), exported to JavaScript as `ChaCha20`.
+#[wasm_bindgen(js_name = ChaCha20)]
+pub struct WasmChaCha20 {
+    cipher: FastChaCha20,
+}
+
+#[wasm_bindgen(js_class = ChaCha20)]
+impl WasmChaCha20 {
+    /// Creates a cipher at the start of the keystream.
+    ///
+    /// # Arguments
+    /// * `key` - 32-byte key.
+    /// * `nonce` - 12-byte nonce.
+    ///
+    /// # Errors
+    /// Throws if `key` or `nonce` has the wrong length.
+    #[wasm_bindgen(constructor)]
+    pub fn new(key: &[u8], nonce: &[u8]) -> Result<WasmChaCha20, JsError> {
+        let cipher = FastChaCha20::new(array::<KEY_BYTES>(key)?, array::<NONCE_BYTES>(nonce)?);
+        Ok(Self { cipher })
+    }
+
+    /// XORs `data` in place with the next bytes of the keystream.
+    pub fn apply(&mut self, data: &mut [u8]) {
+        self.cipher.apply_keystream(data);
+    }
+
+    /// Moves to keystream byte `position` (a `BigInt`).
+    pub fn seek(&mut self, position: u64) {
+        self.cipher.seek(position);
+    }
+
+    /// Returns the current keystream byte position (a `BigInt`).
+    pub fn position(&self) -> u64 {
+        self.cipher.current_pos()
+    }
+}
+
+/// ChaCha20-Poly1305 (RFC 8439), exported to JavaScript as `ChaCha20Poly1305`.
+#[wasm_bindgen(js_name = ChaCha20Poly1305)]
+pub struct WasmChaCha20Poly1305 {
+    aead: aead::ChaCha20Poly1305,
+}
+
+#[wasm_bindgen(js_class = ChaCha20Poly1305)]
+impl WasmChaCha20Poly1305 {
+    /// Creates an AEAD instance for a 32-byte key.
+    ///
+    /// # Errors
+    /// Throws if `key` is not 32 bytes long.
+    #[wasm_bindgen(constructor)]
+    pub fn new(key: &[u8]) -> Result<WasmChaCha20Poly1305, JsError> {
+        Ok(Self { aead: aead::ChaCha20Poly1305::new(array(key)?) })
+    }
+
+    /// Encrypts `plaintext` and returns `ciphertext || tag`.
+    ///
+    /// # Errors
+    /// Throws if `nonce` is not 12 bytes long or `plaintext` is too long.
+    pub fn encrypt(&self, nonce: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, JsError> {
+        let nonce = array(nonce)?;
+        encrypt(plaintext, |buf| self.aead.encrypt_in_place_detached(nonce, aad, buf))
+    }
+
+    /// Verifies and decrypts `ciphertext || tag`, returning the plaintext.
+    ///
+    /// # Errors
+    /// Throws if authentication fails, `nonce` is not 12 bytes long or `sealed` is shorter
+    /// than a tag.
+    pub fn decrypt(&self, nonce: &[u8], sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>, JsError> {
+        let nonce = array(nonce)?;
+        decrypt(sealed, |buf, tag| self.aead.decrypt_in_place_detached(nonce, aad, buf, tag))
+    }
+}
+
+/// XChaCha20-Poly1305, exported to JavaScript as `XChaCha20Poly1305`.
+///
+/// Its 24-byte nonces may be drawn from `crypto.getRandomValues`.
+#[wasm_bindgen(js_name = XChaCha20Poly1305)]
+pub struct WasmXChaCha20Poly1305 {
+    aead: aead::XChaCha20Poly1305,
+}
+
+#[wasm_bindgen(js_class = XChaCha20Poly1305)]
+impl WasmXChaCha20Poly1305 {
+    /// Creates an AEAD instance for a 32-byte key.
+    ///
+    /// # Errors
+    /// Throws if `key` is not 32 bytes long.
+    #[wasm_bindgen(constructor)]
+    pub fn new(key: &[u8]) -> Result<WasmXChaCha20Poly1305, JsError> {
+        Ok(Self { aead: aead::XChaCha20Poly1305::new(array(key)?) })
+    }
+
+    /// Encrypts `plaintext` and returns `ciphertext || tag`.
+    ///
+    /// # Errors
+    /// Throws if `nonce` is not 24 bytes long or `plaintext` is too long.
+    pub fn encrypt(&self, nonce: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, JsError> {
+        let nonce = array::<X_NONCE_BYTES>(nonce)?;
+        encrypt(plaintext, |buf| self.aead.encrypt_in_place_detached(nonce, aad, buf))
+    }
+
+    /// Verifies and decrypts `ciphertext || tag`, returning the plaintext.
+    ///
+    /// # Errors
+    /// Throws if authentication fails, `nonce` is not 24 bytes long or `sealed` is shorter
+    /// than a tag.
+    pub fn decrypt(&self, nonce: &[u8], sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>, JsError> {
+        let nonce = array::<X_NONCE_BYTES>(nonce)?;
+        decrypt(sealed, |buf, tag| self.aead.decrypt_in_place_detached(nonce, aad, buf, tag))
+    }
+}
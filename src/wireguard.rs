@@ -0,0 +1,276 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! WireGuard transport data and cookie reply encryption.
+//!
+//! - Transport data messages (type 4) are `type || reserved || receiver index (LE32) ||
+//!   counter (LE64) || ChaCha20-Poly1305(packet padded to 16 bytes)`, with the nonce
+//!   `0u32 || counter.to_le_bytes()` and empty AAD. [`TransportKey`] seals and opens them.
+//! - Cookie replies encrypt the 16-byte cookie with XChaCha20-Poly1305, using the `mac1`
+//!   of the message being answered as AAD: [`seal_cookie`] and [`open_cookie`].
+//! - [`ReplayWindow`] is the sliding-window counter check receivers apply after a
+//!   message authenticates.
+//!
+//! The handshake, key derivation (BLAKE2s) and timers are out of scope.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fast_chacha::wireguard::{transport_len, ReplayWindow, TransportKey};
+//!
+//! let key = TransportKey::new(&[8u8; 32]);
+//! let packet = b"an IP packet";
+//!
+//! let mut message = vec![0u8; transport_len(packet.len())];
+//! key.seal(0x1234, 0, packet, &mut message).unwrap();
+//!
+//! let mut window = ReplayWindow::new();
+//! let (counter, padded) = key.open(&mut message).unwrap();
+//! assert!(window.accept(counter));
+//! assert_eq!(&padded[..packet.len()], packet);
+//! assert!(!window.accept(counter)); // replayed
+//! ```
+
+use crate::aead::{ChaCha20Poly1305, XChaCha20Poly1305};
+pub use crate::aead::{KEY_BYTES, TAG_BYTES, X_NONCE_BYTES as COOKIE_NONCE_BYTES};
+use crate::Error;
+
+/// Message type of transport data messages.
+pub const MESSAGE_TRANSPORT_DATA: u8 = 4;
+/// Size of the transport data header (type, reserved, receiver index, counter), in bytes.
+pub const TRANSPORT_HEADER_BYTES: usize = 16;
+/// Counters at or above this value are never used (`2^64 - 2^13 - 1`).
+pub const REJECT_AFTER_MESSAGES: u64 = u64::MAX - (1 << 13);
+/// Size of a cookie, in bytes.
+pub const COOKIE_BYTES: usize = 16;
+/// Size of an encrypted cookie (cookie and tag), in bytes.
+pub const ENCRYPTED_COOKIE_BYTES: usize = COOKIE_BYTES + TAG_BYTES;
+
+/// Number of 64-bit words in the replay bitmap.
+const RING_BLOCKS: u64 = 32;
+/// Number of counters behind the highest one that are still accepted.
+const WINDOW_SIZE: u64 = (RING_BLOCKS - 1) * 64;
+
+/// Returns the length of the transport data message carrying a `packet_len`-byte packet.
+pub fn transport_len(packet_len: usize) -> usize {
+    TRANSPORT_HEADER_BYTES + packet_len.next_multiple_of(16) + TAG_BYTES
+}
+
+/// Reads the receiver index of a transport data message, used to find its session key.
+///
+/// # Errors
+/// [`Error::InvalidLength`] if `message` is not a transport data message.
+pub fn receiver_index(message: &[u8]) -> Result<u32, Error> {
+    if message.len() < TRANSPORT_HEADER_BYTES + TAG_BYTES
+        || message[..4] != [MESSAGE_TRANSPORT_DATA, 0, 0, 0]
+    {
+        return Err(Error::InvalidLength);
+    }
+    Ok(u32::from_le_bytes(message[4..8].try_into().unwrap()))
+}
+
+/// Builds the AEAD nonce for a message counter.
+#[inline(always)]
+fn nonce(counter: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[4..].copy_from_slice(&counter.to_le_bytes());
+    nonce
+}
+
+/// Sending or receiving key of a WireGuard session.
+#[derive(Clone)]
+pub struct TransportKey {
+    aead: ChaCha20Poly1305,
+}
+
+impl TransportKey {
+    /// Creates a transport key from the 32-byte session key.
+    ///
+    /// # Arguments
+    /// * `key` - `T_send` or `T_recv` from the handshake.
+    pub fn new(key: &[u8; KEY_BYTES]) -> Self {
+        Self { aead: ChaCha20Poly1305::new(key) }
+    }
+
+    /// Builds and encrypts a transport data message, and returns its length.
+    ///
+    /// The packet is zero-padded to a multiple of 16 bytes before encryption.
+    ///
+    /// # Arguments
+    /// * `receiver` - The peer's receiver index.
+    /// * `counter` - Message counter; must never repeat for the same key.
+    /// * `packet` - Packet to encrypt (empty for a keepalive).
+    /// * `out` - Output buffer of at least [`transport_len`]`(packet.len())` bytes.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidLength`] if `out` is too small.
+    /// - [`Error::NonceExhausted`] if `counter` reached [`REJECT_AFTER_MESSAGES`].
+    pub fn seal(
+        &self,
+        receiver: u32,
+        counter: u64,
+        packet: &[u8],
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        let len = transport_len(packet.len());
+        if out.len() < len {
+            return Err(Error::InvalidLength);
+        }
+        if counter >= REJECT_AFTER_MESSAGES {
+            return Err(Error::NonceExhausted);
+        }
+
+        let (header, body) = out[..len].split_at_mut(TRANSPORT_HEADER_BYTES);
+        header[..4].copy_from_slice(&[MESSAGE_TRANSPORT_DATA, 0, 0, 0]);
+        header[4..8].copy_from_slice(&receiver.to_le_bytes());
+        header[8..].copy_from_slice(&counter.to_le_bytes());
+
+        let (data, tag) = body.split_at_mut(body.len() - TAG_BYTES);
+        data[..packet.len()].copy_from_slice(packet);
+        data[packet.len()..].fill(0);
+        tag.copy_from_slice(&self.aead.encrypt_in_place_detached(&nonce(counter), &[], data)?);
+
+        Ok(len)
+    }
+
+    /// Decrypts a transport data message in place.
+    ///
+    /// Returns the message counter and the padded packet. The counter must then be passed
+    /// to [`ReplayWindow::accept`]; the padding can only be removed using the length field
+    /// of the inner IP header.
+    ///
+    /// # Arguments
+    /// * `message` - One complete transport data message.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidLength`] if `message` is not a transport data message.
+    /// - [`Error::NonceExhausted`] if the counter is at or above [`REJECT_AFTER_MESSAGES`].
+    /// - [`Error::AuthenticationFailed`] if the tag does not verify.
+    pub fn open<'a>(&self, message: &'a mut [u8]) -> Result<(u64, &'a [u8]), Error> {
+        receiver_index(message)?;
+        let counter = u64::from_le_bytes(message[8..16].try_into().unwrap());
+        if counter >= REJECT_AFTER_MESSAGES {
+            return Err(Error::NonceExhausted);
+        }
+
+        let body = &mut message[TRANSPORT_HEADER_BYTES..];
+        let (data, tag) = body.split_at_mut(body.len() - TAG_BYTES);
+        let tag: &[u8; TAG_BYTES] = (&*tag).try_into().unwrap();
+        self.aead.decrypt_in_place_detached(&nonce(counter), &[], data, tag)?;
+
+        let data: &'a [u8] = data;
+        Ok((counter, data))
+    }
+}
+
+/// Encrypts a cookie for a cookie reply message.
+///
+/// # Arguments
+/// * `key` - `HASH(LABEL-COOKIE || responder public key)`.
+/// * `nonce` - Random 24-byte nonce, sent in the clear in the reply.
+/// * `cookie` - The cookie to send.
+/// * `mac1` - `mac1` of the message being answered.
+pub fn seal_cookie(
+    key: &[u8; KEY_BYTES],
+    nonce: &[u8; COOKIE_NONCE_BYTES],
+    cookie: &[u8; COOKIE_BYTES],
+    mac1: &[u8; 16],
+) -> [u8; ENCRYPTED_COOKIE_BYTES] {
+    let mut out = [0u8; ENCRYPTED_COOKIE_BYTES];
+    let (data, tag) = out.split_at_mut(COOKIE_BYTES);
+    data.copy_from_slice(cookie);
+    // A 16-byte message is always within the length limit.
+    tag.copy_from_slice(
+        &XChaCha20Poly1305::new(key).encrypt_in_place_detached(nonce, mac1, data).unwrap(),
+    );
+    out
+}
+
+/// Decrypts the cookie of a cookie reply message.
+///
+/// # Arguments
+/// * `key` - `HASH(LABEL-COOKIE || responder public key)`.
+/// * `nonce` - Nonce from the reply.
+/// * `encrypted` - Encrypted cookie from the reply.
+/// * `mac1` - `mac1` of the message the reply answers.
+///
+/// # Errors
+/// [`Error::AuthenticationFailed`] if the tag does not verify.
+pub fn open_cookie(
+    key: &[u8; KEY_BYTES],
+    nonce: &[u8; COOKIE_NONCE_BYTES],
+    encrypted: &[u8; ENCRYPTED_COOKIE_BYTES],
+    mac1: &[u8; 16],
+) -> Result<[u8; COOKIE_BYTES], Error> {
+    let mut cookie: [u8; COOKIE_BYTES] = encrypted[..COOKIE_BYTES].try_into().unwrap();
+    let tag: &[u8; TAG_BYTES] = encrypted[COOKIE_BYTES..].try_into().unwrap();
+    XChaCha20Poly1305::new(key).decrypt_in_place_detached(nonce, mac1, &mut cookie, tag)?;
+    Ok(cookie)
+}
+
+/// Sliding-window replay protection for received counters (RFC 6479 bitmap).
+///
+/// Counters more than 1984 below the highest accepted one, and counters already seen,
+/// are rejected.
+#[derive(Clone)]
+pub struct ReplayWindow {
+    /// Highest counter accepted so far.
+    last: u64,
+    /// Ring of seen-counter bits, indexed by `counter / 64 % RING_BLOCKS`.
+    bitmap: [u64; RING_BLOCKS as usize],
+}
+
+impl Default for ReplayWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReplayWindow {
+    /// Creates an empty window, as for a new session.
+    pub fn new() -> Self {
+        Self { last: 0, bitmap: [0; RING_BLOCKS as usize] }
+    }
+
+    /// Records `counter` as received and returns `true` if it is new and inside the window.
+    ///
+    /// Only call this for messages that authenticated, so forgeries cannot move the window.
+    pub fn accept(&mut self, counter: u64) -> bool {
+        if counter >= REJECT_AFTER_MESSAGES {
+            return false;
+        }
+        let block = counter / 64;
+
+        if counter > self.last {
+            let current = self.last / 64;
+            let diff = (block - current).min(RING_BLOCKS);
+            for i in 1..=diff {
+                self.bitmap[((current + i) % RING_BLOCKS) as usize] = 0;
+            }
+            self.last = counter;
+        } else if self.last - counter > WINDOW_SIZE {
+            return false;
+        }
+
+        let word = &mut self.bitmap[(block % RING_BLOCKS) as usize];
+        let bit = 1u64 << (counter % 64);
+        if *word & bit != 0 {
+            return false;
+        }
+        *word |= bit;
+        true
+    }
+}
@@ -0,0 +1,93 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Adiantum Test Vectors
+ *
+ * `tests/data/adiantum.txt` holds a selection of the reference vectors published with the
+ * Adiantum design (github.com/google/adiantum): XChaCha12 for every message length
+ * (16 to 4096 bytes) and tweak length (0, 17, 32 bytes), plus XChaCha8 and XChaCha20 up to
+ * 512 bytes. The Linux kernel's `crypto/testmgr.h` vectors are not included yet.
+ */
+
+#![cfg(feature = "adiantum")]
+
+use fast_chacha::adiantum::Adiantum;
+use fast_chacha::Error;
+
+/// Decodes a hex string into bytes; `-` stands for an empty string.
+fn hex(s: &str) -> Vec<u8> {
+    if s == "-" {
+        return Vec::new();
+    }
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+/// (double rounds, key, tweak, plaintext, ciphertext)
+type Vector = (usize, [u8; 32], Vec<u8>, Vec<u8>, Vec<u8>);
+
+fn vectors() -> Vec<Vector> {
+    include_str!("data/adiantum.txt")
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| {
+            let f: Vec<&str> = line.split(' ').collect();
+            (f[0].parse().unwrap(), hex(f[1]).try_into().unwrap(), hex(f[2]), hex(f[3]), hex(f[4]))
+        })
+        .collect()
+}
+
+/// Encryption and decryption must match every reference vector.
+#[test]
+fn reference_vectors() {
+    let vectors = vectors();
+    assert_eq!(vectors.len(), 42);
+
+    for (double_rounds, key, tweak, plaintext, ciphertext) in vectors {
+        let cipher = Adiantum::new_with_double_rounds(&key, double_rounds);
+        let mut sector = plaintext.clone();
+
+        cipher.encrypt(&tweak, &mut sector).unwrap();
+        assert!(
+            sector == ciphertext,
+            "encryption mismatch: {} rounds, {} bytes, {}-byte tweak",
+            2 * double_rounds,
+            plaintext.len(),
+            tweak.len()
+        );
+        cipher.decrypt(&tweak, &mut sector).unwrap();
+        assert!(sector == plaintext, "decryption mismatch: {} bytes", plaintext.len());
+    }
+}
+
+/// A single flipped bit anywhere changes the whole sector, and the tweak matters.
+#[test]
+fn wide_block_diffusion() {
+    let cipher = Adiantum::new(&[3u8; 32]);
+    let mut a = [0u8; 512];
+    let mut b = [0u8; 512];
+    b[0] = 1;
+    cipher.encrypt(&[0u8; 32], &mut a).unwrap();
+    cipher.encrypt(&[0u8; 32], &mut b).unwrap();
+    let same = a.iter().zip(b.iter()).filter(|(x, y)| x == y).count();
+    assert!(same < 16, "{} of 512 ciphertext bytes unchanged", same);
+
+    let mut c = [0u8; 512];
+    cipher.encrypt(&[1u8; 32], &mut c).unwrap();
+    assert_ne!(a, c);
+
+    assert_eq!(cipher.encrypt(&[], &mut [0u8; 15]), Err(Error::InvalidLength));
+}
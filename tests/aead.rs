@@ -0,0 +1,62 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # ChaCha20-Poly1305 AEAD Test Vector
+ *
+ * RFC 8439 §2.8.2 ("Ladies and Gentlemen of the class of '99...").
+ */
+
+use fast_chacha::aead::ChaCha20Poly1305;
+use fast_chacha::Error;
+
+/// Decodes a hex string into bytes.
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+const PLAINTEXT: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only \
+one tip for the future, sunscreen would be it.";
+
+/// Encrypting and decrypting the RFC 8439 §2.8.2 vector, and rejecting a bad tag.
+#[test]
+fn test_rfc8439_aead() {
+    let key: [u8; 32] = core::array::from_fn(|i| 0x80 + i as u8);
+    let nonce: [u8; 12] = hex("070000004041424344454647").try_into().unwrap();
+    let aad = hex("50515253c0c1c2c3c4c5c6c7");
+    let expected = hex("d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6\
+         3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36\
+         92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc\
+         3ff4def08e4b7a9de576d26586cec64b6116");
+    let expected_tag = hex("1ae10b594f09e26a7e902ecbd0600691");
+
+    let aead = ChaCha20Poly1305::new(&key);
+    let mut buf = PLAINTEXT.to_vec();
+    let tag = aead.encrypt_in_place_detached(&nonce, &aad, &mut buf).unwrap();
+    assert_eq!(buf, expected);
+    assert_eq!(tag.to_vec(), expected_tag);
+
+    let mut bad_tag = tag;
+    bad_tag[15] ^= 1;
+    assert_eq!(
+        aead.decrypt_in_place_detached(&nonce, &aad, &mut buf, &bad_tag),
+        Err(Error::AuthenticationFailed)
+    );
+    assert_eq!(buf, expected, "buffer modified by a failed decryption");
+
+    aead.decrypt_in_place_detached(&nonce, &aad, &mut buf, &tag).unwrap();
+    assert_eq!(buf, PLAINTEXT);
+}
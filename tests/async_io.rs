@@ -0,0 +1,118 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Tokio adapter tests
+ *
+ * Runs `AsyncChaChaWriter` and `AsyncChaChaReader` over in-memory duplex streams and
+ * over a writer that only accepts a few bytes at a time, checking that the ciphertext
+ * always matches a single `apply_keystream` call.
+ */
+
+#![cfg(feature = "tokio")]
+
+use fast_chacha::async_io::{AsyncChaChaReader, AsyncChaChaWriter};
+use fast_chacha::FastChaCha20;
+use rand::RngCore;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const KEY: [u8; 32] = [0x11; 32];
+const NONCE: [u8; 12] = [0x22; 12];
+
+/// Writer that returns `Pending` on every other call and accepts at most `max` bytes.
+struct Trickle {
+    out: Vec<u8>,
+    max: usize,
+    stall: bool,
+}
+
+impl AsyncWrite for Trickle {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        data: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.stall = !self.stall;
+        if self.stall {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        let n = data.len().min(self.max);
+        self.out.extend_from_slice(&data[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Data sent through a small duplex pipe must arrive decrypted and intact.
+#[tokio::test]
+async fn duplex_roundtrip() {
+    let mut plain = vec![0u8; 200_000];
+    rand::rng().fill_bytes(&mut plain);
+
+    let (client, server) = tokio::io::duplex(1000);
+    let mut writer = AsyncChaChaWriter::new(client, FastChaCha20::new(&KEY, &NONCE));
+    let mut reader = AsyncChaChaReader::new(server, FastChaCha20::new(&KEY, &NONCE));
+
+    let sent = plain.clone();
+    let send = tokio::spawn(async move {
+        writer.write_all(&sent).await.unwrap();
+        writer.shutdown().await.unwrap();
+    });
+
+    let mut received = Vec::new();
+    reader.read_to_end(&mut received).await.unwrap();
+    send.await.unwrap();
+
+    assert!(received == plain, "data corrupted in transit");
+}
+
+/// Small writes must stay buffered until flushed, and partial writes and `Pending` must
+/// keep the pending ciphertext instead of encrypting it again.
+#[tokio::test]
+async fn partial_writes_keep_keystream_in_sync() {
+    let mut plain = vec![0u8; 40_000];
+    rand::rng().fill_bytes(&mut plain);
+
+    let mut expected = plain.clone();
+    FastChaCha20::new(&KEY, &NONCE).apply_keystream(&mut expected);
+
+    let trickle = Trickle { out: Vec::new(), max: 37, stall: false };
+    let mut writer = AsyncChaChaWriter::new(trickle, FastChaCha20::new(&KEY, &NONCE));
+    writer.write_all(&plain[..1000]).await.unwrap();
+    assert_eq!(writer.cipher().current_pos(), 0);
+    assert!(writer.get_ref().out.is_empty());
+
+    // Larger than the buffer, so it drains through the trickle while writing.
+    writer.write_all(&plain[1000..]).await.unwrap();
+    let sent = writer.get_ref().out.len();
+    assert!(sent > 0 && sent < plain.len());
+    assert_eq!(writer.cipher().current_pos() % fast_chacha::io::BUFFER_SIZE as u64, 0);
+
+    writer.flush().await.unwrap();
+    assert_eq!(writer.cipher().current_pos(), plain.len() as u64);
+    assert!(writer.get_ref().out == expected, "ciphertext differs from apply_keystream");
+}
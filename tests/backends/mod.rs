@@ -0,0 +1,143 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Keystream Backends Under Test
+ *
+ * Every keystream implementation compiled into the crate behind one signature, so that the
+ * vector suites in `rfc8439.rs` and `wycheproof.rs` run against each of them, plus a
+ * ChaCha20-Poly1305 construction that takes the backend as a parameter.
+ */
+
+use fast_chacha::batch::{BatchJob, ChaChaBatch};
+use fast_chacha::fallback_chacha20::{hchacha, keystream4};
+use fast_chacha::poly1305::{verify_tag, Poly1305};
+use fast_chacha::FastChaCha20;
+
+/// XORs the keystream for (key, nonce) starting at block `counter` into the buffer.
+pub type Backend = fn(&[u8; 32], &[u8; 12], u32, &mut [u8]);
+
+/// Named backends; `asm` is the dispatching path and runs the Rust fallback on targets
+/// without assembly.
+pub const BACKENDS: &[(&str, Backend)] = &[
+    ("asm", asm),
+    ("asm-b2b", asm_b2b),
+    #[cfg(feature = "rayon")]
+    ("asm-parallel", asm_parallel),
+    ("fallback", fallback),
+    ("simd4", simd4),
+    ("batch", batch),
+];
+
+fn asm(key: &[u8; 32], nonce: &[u8; 12], counter: u32, data: &mut [u8]) {
+    FastChaCha20::new_with_counter(*key, *nonce, counter).apply_keystream(data);
+}
+
+fn asm_b2b(key: &[u8; 32], nonce: &[u8; 12], counter: u32, data: &mut [u8]) {
+    let input = data.to_vec();
+    FastChaCha20::new_with_counter(*key, *nonce, counter)
+        .apply_keystream_b2b(&input, data)
+        .unwrap();
+}
+
+#[cfg(feature = "rayon")]
+fn asm_parallel(key: &[u8; 32], nonce: &[u8; 12], counter: u32, data: &mut [u8]) {
+    FastChaCha20::new_with_counter(*key, *nonce, counter).apply_keystream_parallel(data);
+}
+
+fn fallback(key: &[u8; 32], nonce: &[u8; 12], counter: u32, data: &mut [u8]) {
+    FastChaCha20::new_with_counter(*key, *nonce, counter).apply_keystream_pure(data, 10);
+}
+
+fn simd4(key: &[u8; 32], nonce: &[u8; 12], counter: u32, data: &mut [u8]) {
+    let key_words = words::<8>(key);
+    let nonce_words = words::<3>(nonce);
+    for (i, chunk) in data.chunks_mut(256).enumerate() {
+        let base = counter.wrapping_add(4 * i as u32);
+        let counters = core::array::from_fn(|lane| {
+            [base.wrapping_add(lane as u32), nonce_words[0], nonce_words[1], nonce_words[2]]
+        });
+        let mut blocks = [[0u8; 64]; 4];
+        keystream4(&mut blocks, &key_words, &counters, 10);
+        for (b, k) in chunk.iter_mut().zip(blocks.as_flattened()) {
+            *b ^= k;
+        }
+    }
+}
+
+fn batch(key: &[u8; 32], nonce: &[u8; 12], counter: u32, data: &mut [u8]) {
+    ChaChaBatch::new(key).process(&mut [BatchJob { nonce: *nonce, counter, data }]);
+}
+
+/// Reads `bytes` as little-endian 32-bit words.
+fn words<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    core::array::from_fn(|i| u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap()))
+}
+
+/// Derives the XChaCha20 subkey and 96-bit nonce from a 192-bit nonce.
+pub fn xchacha_subkey(key: &[u8; 32], nonce: &[u8; 24]) -> ([u8; 32], [u8; 12]) {
+    let subkey_words = hchacha(&words::<8>(key), &words::<4>(nonce), 10);
+    let mut subkey = [0u8; 32];
+    for (out, w) in subkey.chunks_exact_mut(4).zip(subkey_words) {
+        out.copy_from_slice(&w.to_le_bytes());
+    }
+    let mut chacha_nonce = [0u8; 12];
+    chacha_nonce[4..].copy_from_slice(&nonce[16..]);
+    (subkey, chacha_nonce)
+}
+
+/// Computes the RFC 8439 §2.8 tag with the Poly1305 key taken from block 0.
+fn tag(backend: Backend, key: &[u8; 32], nonce: &[u8; 12], aad: &[u8], ct: &[u8]) -> [u8; 16] {
+    let mut poly_key = [0u8; 32];
+    backend(key, nonce, 0, &mut poly_key);
+    let mut mac = Poly1305::new(&poly_key);
+    mac.update(aad);
+    mac.pad();
+    mac.update(ct);
+    mac.pad();
+    mac.update(&(aad.len() as u64).to_le_bytes());
+    mac.update(&(ct.len() as u64).to_le_bytes());
+    mac.finalize()
+}
+
+/// ChaCha20-Poly1305 encryption in place with the given backend; returns the tag.
+pub fn seal(
+    backend: Backend,
+    key: &[u8; 32],
+    nonce: &[u8; 12],
+    aad: &[u8],
+    buf: &mut [u8],
+) -> [u8; 16] {
+    backend(key, nonce, 1, buf);
+    tag(backend, key, nonce, aad, buf)
+}
+
+/// ChaCha20-Poly1305 decryption in place with the given backend; `buf` is only
+/// decrypted if the tag verifies.
+pub fn open(
+    backend: Backend,
+    key: &[u8; 32],
+    nonce: &[u8; 12],
+    aad: &[u8],
+    buf: &mut [u8],
+    expected: &[u8; 16],
+) -> bool {
+    if !verify_tag(&tag(backend, key, nonce, aad, buf), expected) {
+        return false;
+    }
+    backend(key, nonce, 1, buf);
+    true
+}
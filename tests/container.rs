@@ -0,0 +1,162 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Encrypted file container tests
+ *
+ * Round-trips containers of several lengths around the chunk size with both algorithms,
+ * checks the first record against a ChaCha20-Poly1305 computed by hand from the format
+ * description, and checks that truncation, appended data and header changes are rejected.
+ * With the `cli` feature, also drives the `fast-chacha` binary end to end.
+ */
+
+#![cfg(feature = "std")]
+
+use fast_chacha::aead::ChaCha20Poly1305;
+use fast_chacha::container::{self, Algorithm, Header, Kdf, HEADER_BYTES, TAG_BYTES};
+use fast_chacha::Error;
+
+const KEY: [u8; 32] = [0x5a; 32];
+
+fn header(algorithm: Algorithm, chunk_size: u32) -> Header {
+    let kdf = Kdf::Argon2id { m_cost: 64, t_cost: 1, p_cost: 1 };
+    let mut header = Header::new(algorithm, kdf, [0x11; 16], [0x22; 19]);
+    header.chunk_size = chunk_size;
+    header
+}
+
+fn seal(header: &Header, plain: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let n = container::encrypt(&KEY, header, plain, &mut out).unwrap();
+    assert_eq!(n, plain.len() as u64);
+    out
+}
+
+fn open(file: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut input = file;
+    let header = Header::read_from(&mut input).map_err(into_error)?;
+    let mut out = Vec::new();
+    container::decrypt(&KEY, &header, input, &mut out).map_err(into_error)?;
+    Ok(out)
+}
+
+fn into_error(e: std::io::Error) -> Error {
+    *e.into_inner().unwrap().downcast::<Error>().unwrap()
+}
+
+#[test]
+fn round_trip_around_chunk_boundaries() {
+    let plain: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+    for algorithm in [Algorithm::ChaCha20Poly1305, Algorithm::XChaCha20Poly1305] {
+        let header = header(algorithm, 100);
+        for len in [0, 1, 99, 100, 101, 500, 1000] {
+            let file = seal(&header, &plain[..len]);
+            let records = len / 100 + 1;
+            assert_eq!(file.len(), HEADER_BYTES + len + records * TAG_BYTES);
+            assert_eq!(open(&file).unwrap(), &plain[..len], "{algorithm:?} len {len}");
+        }
+    }
+}
+
+#[test]
+fn header_and_record_layout() {
+    let header = header(Algorithm::ChaCha20Poly1305, 4);
+    let file = seal(&header, b"abcdef");
+
+    let bytes = header.to_bytes();
+    assert_eq!(&file[..HEADER_BYTES], &bytes);
+    assert_eq!(&bytes[..8], b"FCHA\x01\x01\x01\x00");
+    assert_eq!(Header::from_bytes(&bytes).unwrap(), header);
+
+    // Record 1 is the last one: nonce = prefix[..7] || be32(1) || 1.
+    let mut nonce = [0x22u8; 12];
+    nonce[7..].copy_from_slice(&[0, 0, 0, 1, 1]);
+    let mut data = *b"ef";
+    let tag = ChaCha20Poly1305::new(&KEY).encrypt_in_place_detached(&nonce, &bytes, &mut data);
+    let record = &file[HEADER_BYTES + 4 + TAG_BYTES..];
+    assert_eq!(&record[..2], &data);
+    assert_eq!(&record[2..], &tag.unwrap());
+}
+
+#[test]
+fn rejects_truncation_appended_data_and_tampering() {
+    let header = header(Algorithm::XChaCha20Poly1305, 64);
+    let file = seal(&header, &[7u8; 128]);
+
+    // Dropping the empty last record leaves two full records and no end marker.
+    assert_eq!(open(&file[..file.len() - TAG_BYTES]), Err(Error::Truncated));
+    assert_eq!(open(&file[..file.len() - 1]), Err(Error::Truncated));
+
+    // Appended bytes become part of the last record.
+    let mut extended = file.clone();
+    extended.push(0);
+    assert_eq!(open(&extended), Err(Error::AuthenticationFailed));
+
+    // The header is authenticated with every record.
+    let mut tampered = file.clone();
+    tampered[24] ^= 1; // salt
+    assert_eq!(open(&tampered), Err(Error::AuthenticationFailed));
+
+    let mut tampered = file.clone();
+    tampered[HEADER_BYTES + 3] ^= 1;
+    assert_eq!(open(&tampered), Err(Error::AuthenticationFailed));
+
+    let mut bad_magic = file;
+    bad_magic[0] = b'X';
+    assert_eq!(open(&bad_magic), Err(Error::InvalidHeader));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn cli_encrypt_inspect_decrypt() {
+    use std::process::Command;
+
+    let dir = std::env::temp_dir().join(format!("fast-chacha-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = |name: &str| dir.join(name).to_str().unwrap().to_owned();
+    let plain: Vec<u8> = (0..200_000u32).map(|i| (i * 7) as u8).collect();
+    std::fs::write(path("plain"), &plain).unwrap();
+    std::fs::write(path("password"), "correct horse\n").unwrap();
+    std::fs::write(path("wrong"), "battery staple\n").unwrap();
+
+    let run = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_fast-chacha")).args(args).output();
+    let kdf = ["--kdf-memory", "64", "--kdf-iterations", "1"];
+
+    let out = run(&[
+        &["encrypt", "--xchacha", "--password-file", &path("password")][..],
+        &kdf,
+        &[&path("plain"), &path("enc")],
+    ]
+    .concat())
+    .unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+
+    let out = run(&["inspect", &path("enc")]).unwrap();
+    let info = String::from_utf8(out.stdout).unwrap();
+    assert!(info.contains("XChaCha20-Poly1305") && info.contains("m=64 KiB, t=1, p=1"));
+
+    let out = run(&["decrypt", "--password-file", &path("password"), &path("enc"), &path("dec")])
+        .unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(std::fs::read(path("dec")).unwrap(), plain);
+
+    let out =
+        run(&["decrypt", "--password-file", &path("wrong"), &path("enc"), &path("bad")]).unwrap();
+    assert!(!out.status.success());
+    assert!(!dir.join("bad").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,230 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Streaming I/O adapter tests
+ *
+ * Checks that `ChaChaWriter` and `ChaChaReader` produce exactly the same bytes as a
+ * single `apply_keystream` call over the whole buffer, regardless of how the data is
+ * split into writes, reads and seeks.
+ */
+
+#![cfg(feature = "std")]
+
+use fast_chacha::io::{ChaChaReader, ChaChaWriter};
+use fast_chacha::FastChaCha20;
+use rand::RngCore;
+use std::io::{Cursor, IoSliceMut, Read, Seek, SeekFrom, Write};
+
+const KEY: [u8; 32] = [0x42; 32];
+const NONCE: [u8; 12] = [0x24; 12];
+
+/// Encrypts `plain` with one `apply_keystream` call.
+fn one_shot(plain: &[u8]) -> Vec<u8> {
+    let mut data = plain.to_vec();
+    FastChaCha20::new(&KEY, &NONCE).apply_keystream(&mut data);
+    data
+}
+
+/// Applying the keystream in odd-sized pieces must continue the stream exactly.
+#[test]
+fn split_apply_keystream_matches_one_shot() {
+    let mut plain = vec![0u8; 5000];
+    rand::rng().fill_bytes(&mut plain);
+    let expected = one_shot(&plain);
+
+    for step in [1usize, 7, 63, 64, 65, 300, 1000] {
+        let mut cipher = FastChaCha20::new(&KEY, &NONCE);
+        let mut data = plain.clone();
+        for chunk in data.chunks_mut(step) {
+            cipher.apply_keystream(chunk);
+        }
+        assert_eq!(cipher.current_pos(), plain.len() as u64);
+        assert!(data == expected, "keystream diverged for chunk size {}", step);
+    }
+}
+
+/// Writing in irregular chunks through `ChaChaWriter` and reading back through
+/// `ChaChaReader` must round-trip and match the one-shot ciphertext.
+#[test]
+fn writer_reader_roundtrip() {
+    let mut plain = vec![0u8; 100_000];
+    rand::rng().fill_bytes(&mut plain);
+
+    let mut writer = ChaChaWriter::new(Vec::new(), FastChaCha20::new(&KEY, &NONCE));
+    let mut rest = &plain[..];
+    let mut step = 1;
+    while !rest.is_empty() {
+        let n = step.min(rest.len());
+        writer.write_all(&rest[..n]).unwrap();
+        rest = &rest[n..];
+        step = step * 3 + 1;
+    }
+    let ciphertext = writer.into_inner().unwrap();
+    assert!(ciphertext == one_shot(&plain), "ChaChaWriter output differs from apply_keystream");
+
+    let mut reader = ChaChaReader::new(&ciphertext[..], FastChaCha20::new(&KEY, &NONCE));
+    let mut decrypted = Vec::new();
+    let mut small = [0u8; 33];
+    let n = reader.read(&mut small).unwrap();
+    decrypted.extend_from_slice(&small[..n]);
+    reader.read_to_end(&mut decrypted).unwrap();
+    assert!(decrypted == plain, "ChaChaReader did not recover the plaintext");
+}
+
+/// Inner writer that takes at most 100 bytes per call and fails every fifth call.
+struct Flaky {
+    data: Vec<u8>,
+    calls: usize,
+}
+
+impl Write for Flaky {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.calls += 1;
+        if self.calls % 5 == 4 {
+            return Err(std::io::Error::other("flaky"));
+        }
+        let n = buf.len().min(100);
+        self.data.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Small writes must stay buffered and unencrypted until flushed, and a flush interrupted
+/// by errors must resume without encrypting anything twice.
+#[test]
+fn writer_buffers_small_writes() {
+    let mut plain = vec![0u8; 5000];
+    rand::rng().fill_bytes(&mut plain);
+
+    let mut writer =
+        ChaChaWriter::new(Flaky { data: Vec::new(), calls: 0 }, FastChaCha20::new(&KEY, &NONCE));
+    for chunk in plain.chunks(7) {
+        assert_eq!(writer.write(chunk).unwrap(), chunk.len());
+    }
+    assert_eq!(writer.cipher().current_pos(), 0);
+    assert!(writer.get_ref().data.is_empty());
+
+    let mut failures = 0;
+    while let Err(e) = writer.flush() {
+        assert_eq!(e.to_string(), "flaky");
+        failures += 1;
+        // More data arriving between failed flushes must queue up behind the ciphertext.
+        writer.write_all(&plain[..3]).unwrap();
+    }
+    assert!(failures > 0);
+    assert_eq!(writer.cipher().current_pos(), (plain.len() + 3 * failures) as u64);
+
+    let mut expected = plain.clone();
+    for _ in 0..failures {
+        expected.extend_from_slice(&plain[..3]);
+    }
+    let ciphertext = writer.into_inner().unwrap().data;
+    assert!(ciphertext == one_shot(&expected), "ChaChaWriter output differs after flush errors");
+}
+
+/// Seeking a `ChaChaReader` must reposition the keystream along with the inner reader.
+#[test]
+fn reader_seek() {
+    let mut plain = vec![0u8; 40_000];
+    rand::rng().fill_bytes(&mut plain);
+    let ciphertext = one_shot(&plain);
+
+    let mut reader = ChaChaReader::new(Cursor::new(ciphertext), FastChaCha20::new(&KEY, &NONCE));
+    let mut buf = [0u8; 100];
+
+    reader.seek(SeekFrom::Start(12_345)).unwrap();
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf[..], &plain[12_345..12_445]);
+
+    let pos = reader.seek(SeekFrom::Current(-50)).unwrap();
+    assert_eq!(pos, 12_395);
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf[..], &plain[12_395..12_495]);
+
+    reader.seek(SeekFrom::End(-7)).unwrap();
+    let mut tail = Vec::new();
+    reader.read_to_end(&mut tail).unwrap();
+    assert_eq!(&tail[..], &plain[plain.len() - 7..]);
+}
+
+/// Seeks the 32-bit block counter cannot reach must fail and leave the reader in place,
+/// instead of wrapping the keystream or overflowing the position.
+#[test]
+fn reader_seek_past_keystream() {
+    let mut plain = vec![0u8; 1000];
+    rand::rng().fill_bytes(&mut plain);
+    let mut cipher = FastChaCha20::new(&KEY, &NONCE);
+    cipher.seek(100);
+    let mut ciphertext = plain.clone();
+    cipher.clone().apply_keystream(&mut ciphertext);
+
+    let limit = (1u64 << 32) * 64 - 100;
+    let mut reader = ChaChaReader::new(Cursor::new(ciphertext), cipher);
+    let mut buf = [0u8; 10];
+    reader.read_exact(&mut buf).unwrap();
+
+    for target in [SeekFrom::Start(u64::MAX), SeekFrom::Start(limit), SeekFrom::Current(i64::MAX)] {
+        let err = reader.seek(target).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        reader.read_exact(&mut buf).unwrap();
+    }
+    assert_eq!(&buf[..], &plain[30..40]);
+    assert_eq!(
+        reader.seek(SeekFrom::Current(i64::MIN)).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+
+    assert_eq!(reader.seek(SeekFrom::Start(limit - 1)).unwrap(), limit - 1);
+    assert_eq!(reader.cipher().current_pos(), (1u64 << 32) * 64 - 1);
+}
+
+/// Vectored application over irregular buffers must equal one contiguous call,
+/// also when starting in the middle of a block.
+#[test]
+fn vectored_matches_contiguous() {
+    let mut plain = vec![0u8; 3000];
+    rand::rng().fill_bytes(&mut plain);
+
+    let splits = [0usize, 5, 5, 70, 64, 1, 200, 0, 1000, 63];
+    for start in [0u64, 10, 64] {
+        let mut expected = plain.clone();
+        let mut cipher = FastChaCha20::new(&KEY, &NONCE);
+        cipher.seek(start);
+        cipher.apply_keystream(&mut expected);
+
+        let mut data = plain.clone();
+        let mut slices = Vec::new();
+        let mut rest = &mut data[..];
+        for &len in splits.iter() {
+            let (head, tail) = rest.split_at_mut(len);
+            slices.push(IoSliceMut::new(head));
+            rest = tail;
+        }
+        slices.push(IoSliceMut::new(rest));
+
+        let mut vectored = FastChaCha20::new(&KEY, &NONCE);
+        vectored.seek(start);
+        vectored.apply_keystream_vectored(&mut slices);
+
+        assert_eq!(vectored.current_pos(), cipher.current_pos());
+        assert!(data == expected, "vectored output differs at start {}", start);
+    }
+}
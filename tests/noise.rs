@@ -0,0 +1,85 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Noise ChaChaPoly CipherState Tests
+ *
+ * Expected ciphertexts were computed with OpenSSL's ChaCha20-Poly1305 (via Python
+ * `cryptography`) using the Noise `ChaChaPoly` nonce encoding and key 00..1f, including
+ * the key derived by `REKEY`.
+ */
+
+use fast_chacha::noise::{CipherState, TAG_BYTES};
+use fast_chacha::Error;
+
+/// Decodes a hex string into bytes.
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+fn keyed() -> CipherState {
+    let mut cs = CipherState::new();
+    cs.initialize_key(Some(&core::array::from_fn(|i| i as u8)));
+    cs
+}
+
+/// Encryption at nonces 0, 1 and 2 (after `Rekey`) matches the reference.
+#[test]
+fn encrypt_and_rekey_vectors() {
+    let mut cs = keyed();
+    let mut out = [0u8; 64];
+
+    let n = cs.encrypt_with_ad(b"ad", b"noise", &mut out).unwrap();
+    assert_eq!(&out[..n], &hex("76d72b42c8b8448a41eb89b78113ab9d871b53e0fc")[..]);
+    let n = cs.encrypt_with_ad(b"", b"", &mut out).unwrap();
+    assert_eq!(&out[..n], &hex("a8d30d2553bfe08494e6aa8fd5b07296")[..]);
+
+    cs.rekey();
+    assert_eq!(cs.nonce(), 2);
+    let n = cs.encrypt_with_ad(b"", b"after rekey", &mut out).unwrap();
+    assert_eq!(&out[..n], &hex("e5fd9cf295b5288e6bb4bbafa992b959fc8eee2fe87990790c0603")[..]);
+}
+
+/// Decryption recovers the plaintext; failures leave the nonce unchanged.
+#[test]
+fn decrypt_with_ad() {
+    let mut cs = keyed();
+    let mut out = [0u8; 16];
+    let ciphertext = hex("76d72b42c8b8448a41eb89b78113ab9d871b53e0fc");
+
+    assert_eq!(cs.decrypt_with_ad(b"xx", &ciphertext, &mut out), Err(Error::AuthenticationFailed));
+    assert_eq!(cs.nonce(), 0);
+    assert_eq!(cs.decrypt_with_ad(b"ad", &ciphertext, &mut out), Ok(5));
+    assert_eq!(&out[..5], b"noise");
+    assert_eq!(cs.nonce(), 1);
+    assert_eq!(cs.decrypt_with_ad(b"", &[0u8; TAG_BYTES - 1], &mut out), Err(Error::InvalidLength));
+}
+
+/// Without a key data passes through; the reserved nonce is never used.
+#[test]
+fn empty_key_and_nonce_overflow() {
+    let mut cs = CipherState::new();
+    let mut out = [0u8; 32];
+    assert!(!cs.has_key());
+    assert_eq!(cs.encrypt_with_ad(b"", b"clear", &mut out), Ok(5));
+    assert_eq!(&out[..5], b"clear");
+
+    let mut cs = keyed();
+    cs.set_nonce(u64::MAX - 1);
+    assert!(cs.encrypt_with_ad(b"", b"last", &mut out).is_ok());
+    assert_eq!(cs.encrypt_with_ad(b"", b"", &mut out), Err(Error::NonceExhausted));
+    assert_eq!(cs.decrypt_with_ad(b"", &[0u8; TAG_BYTES], &mut out), Err(Error::NonceExhausted));
+}
@@ -0,0 +1,129 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Nonce sequence tests
+ *
+ * Checks the layout and exhaustion of counter nonces, that a persistent counter never
+ * hands out a nonce twice across reopenings, and that the AEADs seal under the nonce a
+ * sequence hands out. With the matching features, also checks random XChaCha nonces and
+ * the debug reuse registry.
+ */
+
+use fast_chacha::nonce::{CounterNonce, NonceSequence};
+use fast_chacha::Error;
+
+#[test]
+fn counter_nonce_layout_and_exhaustion() {
+    let mut nonces = CounterNonce::new([0xaa, 0xbb, 0xcc, 0xdd]);
+    assert_eq!(nonces.next_nonce().unwrap(), [0xaa, 0xbb, 0xcc, 0xdd, 0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(nonces.next_nonce().unwrap(), [0xaa, 0xbb, 0xcc, 0xdd, 0, 0, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(nonces.counter(), Some(2));
+
+    let mut nonces = CounterNonce::starting_at([0; 4], u64::MAX - 1);
+    nonces.next_nonce().unwrap();
+    assert_eq!(nonces.next_nonce().unwrap()[4..], [0xff; 8]);
+    assert_eq!(nonces.next_nonce(), Err(Error::NonceExhausted));
+    assert_eq!(nonces.counter(), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn persistent_counter_survives_reopen() {
+    use fast_chacha::nonce::PersistentCounterNonce;
+    use std::collections::HashSet;
+
+    let path = std::env::temp_dir().join(format!("fast-chacha-nonce-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut seen = HashSet::new();
+
+    // Three "process lifetimes" of 5 nonces each, with 4 counters reserved per update.
+    for run in 0..3u64 {
+        let mut nonces = PersistentCounterNonce::with_reservation(&path, [1, 2, 3, 4], 4).unwrap();
+        // Reopening skips whatever the previous run reserved but did not use.
+        assert_eq!(nonces.counter(), Some(run * 8));
+        for _ in 0..5 {
+            assert!(seen.insert(nonces.next_nonce().unwrap()));
+        }
+    }
+    assert_eq!(std::fs::read(&path).unwrap(), 24u64.to_le_bytes());
+
+    std::fs::write(&path, [0u8; 3]).unwrap();
+    assert!(PersistentCounterNonce::open(&path, [0; 4]).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "getrandom")]
+#[test]
+fn random_xnonces_differ() {
+    use fast_chacha::nonce::RandomXNonce;
+
+    let mut nonces = RandomXNonce;
+    assert_ne!(nonces.next_nonce().unwrap(), nonces.next_nonce().unwrap());
+}
+
+/// Sealing through a sequence returns the nonce it drew, and the pair opens.
+#[test]
+fn aead_draws_from_sequence() {
+    use fast_chacha::aead::ChaCha20Poly1305;
+
+    let aead = ChaCha20Poly1305::new(&[0x5a; 32]);
+    let mut nonces = CounterNonce::starting_at([9; 4], 41);
+    let mut buf = *b"sequenced";
+    let (nonce, tag) = aead.encrypt_next_in_place_detached(&mut nonces, b"ad", &mut buf).unwrap();
+    assert_eq!(nonce, [9, 9, 9, 9, 0, 0, 0, 0, 0, 0, 0, 41]);
+    aead.decrypt_in_place_detached(&nonce, b"ad", &mut buf, &tag).unwrap();
+    assert_eq!(&buf, b"sequenced");
+
+    let mut exhausted = CounterNonce::starting_at([0; 4], u64::MAX);
+    exhausted.next_nonce().unwrap();
+    let res = aead.encrypt_next_in_place_detached(&mut exhausted, &[], &mut buf);
+    assert_eq!(res, Err(Error::NonceExhausted));
+}
+
+/// Two sequences with the same prefix under one key collide on their first nonce.
+#[cfg(all(feature = "nonce-registry", debug_assertions))]
+#[test]
+#[should_panic(expected = "nonce reused")]
+fn registry_panics_on_reuse() {
+    use fast_chacha::aead::ChaCha20Poly1305;
+
+    let aead = ChaCha20Poly1305::new(&[0x3c; 32]);
+    let mut first = CounterNonce::new([0x3c; 4]);
+    let mut second = CounterNonce::new([0x3c; 4]);
+    aead.encrypt_next_in_place_detached(&mut first, &[], &mut [0u8; 4]).unwrap();
+    aead.encrypt_next_in_place_detached(&mut second, &[], &mut [0u8; 4]).unwrap();
+}
+
+/// Seals with an explicit nonce are not recorded, so deterministic protocol seals (like a
+/// Noise rekey done by both peers in one process) may repeat.
+#[cfg(feature = "nonce-registry")]
+#[test]
+fn registry_ignores_explicit_nonces() {
+    use fast_chacha::aead::{ChaCha20Poly1305, XChaCha20Poly1305};
+
+    let key = [0x4d; 32];
+    let mut nonces = CounterNonce::new([0x4d; 4]);
+    let (nonce, _) = ChaCha20Poly1305::new(&key)
+        .encrypt_next_in_place_detached(&mut nonces, &[], &mut [0u8; 4])
+        .unwrap();
+    for _ in 0..2 {
+        ChaCha20Poly1305::new(&key).encrypt_in_place_detached(&nonce, &[], &mut [0u8; 32]).unwrap();
+        XChaCha20Poly1305::new(&key)
+            .encrypt_in_place_detached(&[0x4d; 24], &[], &mut [0u8; 32])
+            .unwrap();
+    }
+}
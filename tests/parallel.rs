@@ -0,0 +1,55 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Parallel keystream tests
+ *
+ * Verifies that `apply_keystream_parallel` produces the same output and leaves the
+ * cipher at the same position as the serial `apply_keystream`.
+ */
+
+#![cfg(feature = "rayon")]
+
+use fast_chacha::FastChaCha20;
+use rand::RngCore;
+
+/// Parallel and serial encryption must agree, including from a mid-block position.
+#[test]
+fn parallel_matches_serial() {
+    let mut rng = rand::rng();
+    let mut key = [0u8; 32];
+    let mut nonce = [0u8; 12];
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut nonce);
+
+    let mut plain = vec![0u8; 3 * 1024 * 1024 + 77];
+    rng.fill_bytes(&mut plain);
+
+    for start in [0u64, 64, 1000] {
+        let mut serial = FastChaCha20::new(&key, &nonce);
+        serial.seek(start);
+        let mut expected = plain.clone();
+        serial.apply_keystream(&mut expected);
+
+        let mut parallel = FastChaCha20::new(&key, &nonce);
+        parallel.seek(start);
+        let mut data = plain.clone();
+        parallel.apply_keystream_parallel(&mut data);
+
+        assert!(data == expected, "parallel output differs at start {}", start);
+        assert_eq!(parallel.current_pos(), serial.current_pos());
+    }
+}
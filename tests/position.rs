@@ -0,0 +1,113 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Stream Position Checkpoints
+ *
+ * Saving a `FastChaCha20` position, encoding it, and resuming the stream from it after a
+ * simulated restart.
+ */
+
+use fast_chacha::position::{Position, ENCODED_LEN};
+use fast_chacha::{Error, FastChaCha20};
+
+/// A stream interrupted mid-block and resumed from the encoded position produces the same
+/// ciphertext as an uninterrupted one.
+#[test]
+fn resume_from_checkpoint_matches_uninterrupted() {
+    let key = [0x11u8; 32];
+    let nonce = [0x22u8; 12];
+    let plain: Vec<u8> = (0..10_000u32).map(|i| (i * 13) as u8).collect();
+
+    let mut expected = plain.clone();
+    FastChaCha20::new(&key, &nonce).apply_keystream(&mut expected);
+
+    let mut out = plain.clone();
+    let saved = {
+        let mut cipher = FastChaCha20::new(&key, &nonce);
+        cipher.apply_keystream(&mut out[..3333]);
+        cipher.position().to_bytes()
+    };
+
+    let position = Position::from_bytes(&saved).unwrap();
+    assert_eq!(position, Position { nonce, counter: 3333 / 64, offset: (3333 % 64) as u8 });
+    let mut resumed = FastChaCha20::resume(&key, &position).unwrap();
+    assert_eq!(resumed.current_pos(), 3333);
+    resumed.apply_keystream(&mut out[3333..]);
+    assert_eq!(out, expected);
+}
+
+/// XChaCha20 streams are recreated from the 192-bit nonce and then repositioned. A position
+/// is refused when the last 8 nonce bytes differ, but the HChaCha20 prefix is not checked.
+#[test]
+fn set_position_on_xchacha20() {
+    let key = [0x33u8; 32];
+    let nonce = [0x44u8; 24];
+
+    let mut expected = vec![0u8; 500];
+    FastChaCha20::new_xchacha20(&key, &nonce).apply_keystream(&mut expected);
+
+    let mut cipher = FastChaCha20::new_xchacha20(&key, &nonce);
+    let mut out = vec![0u8; 500];
+    cipher.apply_keystream(&mut out[..100]);
+    let position = cipher.position();
+
+    let mut resumed = FastChaCha20::new_xchacha20(&key, &nonce);
+    resumed.set_position(&position).unwrap();
+    resumed.apply_keystream(&mut out[100..]);
+    assert_eq!(out, expected);
+
+    let mut other_suffix = nonce;
+    other_suffix[23] ^= 1;
+    let mut other = FastChaCha20::new_xchacha20(&key, &other_suffix);
+    assert_eq!(other.set_position(&position), Err(Error::InvalidPosition));
+
+    let mut other_prefix = nonce;
+    other_prefix[0] ^= 1;
+    let mut other = FastChaCha20::new_xchacha20(&key, &other_prefix);
+    assert_eq!(other.set_position(&position), Ok(()));
+}
+
+/// Malformed encodings are rejected.
+#[test]
+fn rejects_malformed_encodings() {
+    let bytes = Position { nonce: [9u8; 12], counter: 0xdead_beef, offset: 63 }.to_bytes();
+    assert_eq!(bytes[0], 1);
+    assert_eq!(bytes[13..17], [0xef, 0xbe, 0xad, 0xde]);
+    assert!(Position::from_bytes(&bytes).is_ok());
+
+    assert_eq!(Position::from_bytes(&bytes[..ENCODED_LEN - 1]), Err(Error::InvalidLength));
+    let mut bad = bytes;
+    bad[0] = 2;
+    assert_eq!(Position::from_bytes(&bad), Err(Error::InvalidPosition));
+    let mut bad = bytes;
+    bad[17] = 64;
+    assert_eq!(Position::from_bytes(&bad), Err(Error::InvalidPosition));
+
+    let position = Position { nonce: [9u8; 12], counter: 0, offset: 64 };
+    assert_eq!(FastChaCha20::resume(&[0u8; 32], &position).err(), Some(Error::InvalidPosition));
+}
+
+/// With `serde`, positions round-trip through a serializer.
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let mut cipher = FastChaCha20::new(&[1u8; 32], &[2u8; 12]);
+    cipher.seek(1_000_003);
+    let json = serde_json::to_string(&cipher.position()).unwrap();
+    let position: Position = serde_json::from_str(&json).unwrap();
+    assert_eq!(FastChaCha20::resume(&[1u8; 32], &position).unwrap().current_pos(), 1_000_003);
+}
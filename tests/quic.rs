@@ -0,0 +1,94 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # QUIC ChaCha20-Poly1305 Short Header Packet
+ *
+ * RFC 9001 Appendix A.5: packet number 654360564, header 4200bff4, payload 01.
+ */
+
+use fast_chacha::quic::{quic_header_mask, PacketProtection, TAG_BYTES};
+use fast_chacha::Error;
+
+/// Decodes a hex string into bytes.
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+fn arr<const N: usize>(s: &str) -> [u8; N] {
+    hex(s).try_into().unwrap()
+}
+
+const KEY: &str = "c6d98ff3441c3fe1b2182094f69caa2ed4b716b65488960a7a984979fb23e1c8";
+const IV: &str = "e0459b3474bdd0e44a41c144";
+const HP: &str = "25a282b9e82f06f21f488917a4fc8f1b73573685608597d0efcb076b0ab7a7a4";
+const PN: u64 = 654_360_564;
+const PROTECTED: &str = "4cfe4189655e5cd55c41f69080575d7999c25a5bfb";
+
+fn keys() -> PacketProtection {
+    PacketProtection::new(&arr(KEY), &arr(IV), &arr(HP))
+}
+
+/// The header protection mask of RFC 9001 A.5.
+#[test]
+fn header_mask_rfc9001() {
+    let mask = quic_header_mask(&arr(HP), &arr("5e5cd55c41f69080575d7999c25a5bfb"));
+    assert_eq!(mask.to_vec(), hex("aefefe7d03"));
+}
+
+/// Protecting the A.5 packet must produce the RFC bytes.
+#[test]
+fn encrypt_packet_rfc9001() {
+    let mut packet = hex("4200bff401");
+    packet.extend_from_slice(&[0u8; TAG_BYTES]);
+    keys().encrypt_packet(PN, &mut packet, 4).unwrap();
+    assert_eq!(packet, hex(PROTECTED));
+}
+
+/// Unprotecting the A.5 packet must recover the full packet number and payload, and a
+/// forged packet must be rejected and left unchanged.
+#[test]
+fn decrypt_packet_rfc9001() {
+    let mut packet = hex(PROTECTED);
+    let (pn, header_len) = keys().decrypt_packet(&mut packet, 1, PN - 1).unwrap();
+    assert_eq!((pn, header_len), (PN, 4));
+    assert_eq!(&packet[..header_len + 1], &hex("4200bff401")[..]);
+
+    let mut forged = hex(PROTECTED);
+    forged[4] ^= 1;
+    assert_eq!(keys().decrypt_packet(&mut forged, 1, PN - 1), Err(Error::AuthenticationFailed));
+    assert_eq!(forged[..4], hex(PROTECTED)[..4]);
+}
+
+/// Long header round trip: only the low 4 bits of the first byte are protected.
+#[test]
+fn long_header_roundtrip() {
+    // Initial packet: flags (4-byte packet number), version, DCID, SCID, token, length, pn.
+    let mut header = hex("c300000001088394c8f03e5157080000449e");
+    header.extend_from_slice(&2u32.to_be_bytes());
+    let mut packet = header.clone();
+    packet.extend_from_slice(&[0x06u8; 40]);
+    packet.extend_from_slice(&[0u8; TAG_BYTES]);
+
+    keys().encrypt_packet(2, &mut packet, header.len()).unwrap();
+    assert_eq!(packet[0] & 0xf0, 0xc0);
+    assert_eq!(packet[1..header.len() - 4], header[1..header.len() - 4]);
+
+    let (pn, header_len) = keys().decrypt_packet(&mut packet, header.len() - 4, 1).unwrap();
+    assert_eq!((pn, header_len), (2, header.len()));
+    assert_eq!(&packet[..header_len], &header[..]);
+    assert_eq!(&packet[header_len..header_len + 40], &[0x06u8; 40][..]);
+}
@@ -0,0 +1,303 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # RFC 8439 Appendix A Test Vectors
+ *
+ * All test vectors from RFC 8439 Appendix A: the ChaCha20 block function (A.1), ChaCha20
+ * encryption (A.2), Poly1305 (A.3), Poly1305 key generation (A.4) and ChaCha20-Poly1305
+ * decryption (A.5).
+ *
+ * The keystream vectors run against every backend in `backends/mod.rs`, both on their own
+ * and at the front of a longer buffer, so the multi-block assembly paths see them too.
+ */
+
+#[allow(dead_code)]
+mod backends;
+
+use backends::BACKENDS;
+use fast_chacha::aead::ChaCha20Poly1305;
+use fast_chacha::poly1305::Poly1305;
+
+/// Decodes a hex string into bytes.
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+const IETF_CONTRIBUTION: &[u8] = b"Any submission to the IETF intended by the Contributor for \
+publication as all or part of an IETF Internet-Draft or RFC and any statement made within the \
+context of an IETF activity is considered an \"IETF Contribution\". Such statements include oral \
+statements in IETF sessions, as well as written and electronic communications made at any time \
+or place, which are addressed to";
+
+const JABBERWOCKY: &[u8] = b"'Twas brillig, and the slithy toves\nDid gyre and gimble in the \
+wabe:\nAll mimsy were the borogoves,\nAnd the mome raths outgrabe.";
+
+const KEY_0: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+const KEY_1: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+const KEY_JABBERWOCKY: &str = "1c9240a5eb55d38af333888604f6b5f0473917c1402b80099dca5cbc207075c0";
+const NONCE_0: &str = "000000000000000000000000";
+const NONCE_2: &str = "000000000000000000000002";
+
+/// Zero bytes appended to every keystream vector for the second pass.
+const TAIL: usize = 1024;
+
+/// Checks that every backend turns `input` into `expected` under (key, nonce, counter),
+/// alone and followed by [`TAIL`] more bytes.
+fn check_keystream(
+    vector: &str,
+    key: &str,
+    nonce: &str,
+    counter: u32,
+    input: &[u8],
+    expected: &[u8],
+) {
+    let key: [u8; 32] = hex(key).try_into().unwrap();
+    let nonce: [u8; 12] = hex(nonce).try_into().unwrap();
+
+    for (name, backend) in BACKENDS {
+        let mut buf = input.to_vec();
+        backend(&key, &nonce, counter, &mut buf);
+        assert_eq!(buf, expected, "{vector}: {name} backend");
+
+        let mut long = input.to_vec();
+        long.resize(input.len() + TAIL, 0);
+        backend(&key, &nonce, counter, &mut long);
+        assert_eq!(&long[..input.len()], expected, "{vector}: {name} backend, long buffer");
+    }
+}
+
+/// A.1: ChaCha20 block function, test vectors #1 to #5.
+#[test]
+fn a1_block_function() {
+    let vectors = [
+        (
+            KEY_0,
+            NONCE_0,
+            0,
+            "76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7\
+             da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586",
+        ),
+        (
+            KEY_0,
+            NONCE_0,
+            1,
+            "9f07e7be5551387a98ba977c732d080dcb0f29a048e3656912c6533e32ee7aed\
+             29b721769ce64e43d57133b074d839d531ed1f28510afb45ace10a1f4b794d6f",
+        ),
+        (
+            KEY_1,
+            NONCE_0,
+            1,
+            "3aeb5224ecf849929b9d828db1ced4dd832025e8018b8160b82284f3c949aa5a\
+             8eca00bbb4a73bdad192b5c42f73f2fd4e273644c8b36125a64addeb006c13a0",
+        ),
+        (
+            "00ff000000000000000000000000000000000000000000000000000000000000",
+            NONCE_0,
+            2,
+            "72d54dfbf12ec44b362692df94137f328fea8da73990265ec1bbbea1ae9af0ca\
+             13b25aa26cb4a648cb9b9d1be65b2c0924a66c54d545ec1b7374f4872e99f096",
+        ),
+        (
+            KEY_0,
+            NONCE_2,
+            0,
+            "c2c64d378cd536374ae204b9ef933fcd1a8b2288b3dfa49672ab765b54ee27c7\
+             8a970e0e955c14f3a88e741b97c286f75f8fc299e8148362fa198a39531bed6d",
+        ),
+    ];
+    for (i, (key, nonce, counter, block)) in vectors.into_iter().enumerate() {
+        let vector = format!("A.1 #{}", i + 1);
+        check_keystream(&vector, key, nonce, counter, &[0u8; 64], &hex(block));
+    }
+}
+
+/// A.2: ChaCha20 encryption, test vectors #1 to #3.
+#[test]
+fn a2_encryption() {
+    check_keystream(
+        "A.2 #1",
+        KEY_0,
+        NONCE_0,
+        0,
+        &[0u8; 64],
+        &hex("76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7\
+         da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586"),
+    );
+    check_keystream(
+        "A.2 #2",
+        KEY_1,
+        NONCE_2,
+        1,
+        IETF_CONTRIBUTION,
+        &hex("a3fbf07df3fa2fde4f376ca23e82737041605d9f4f4f57bd8cff2c1d4b7955ec\
+         2a97948bd3722915c8f3d337f7d370050e9e96d647b7c39f56e031ca5eb6250d\
+         4042e02785ececfa4b4bb5e8ead0440e20b6e8db09d881a7c6132f420e527950\
+         42bdfa7773d8a9051447b3291ce1411c680465552aa6c405b7764d5e87bea85a\
+         d00f8449ed8f72d0d662ab052691ca66424bc86d2df80ea41f43abf937d3259d\
+         c4b2d0dfb48a6c9139ddd7f76966e928e635553ba76c5c879d7b35d49eb2e62b\
+         0871cdac638939e25e8a1e0ef9d5280fa8ca328b351c3c765989cbcf3daa8b6c\
+         cc3aaf9f3979c92b3720fc88dc95ed84a1be059c6499b9fda236e7e818b04b0b\
+         c39c1e876b193bfe5569753f88128cc08aaa9b63d1a16f80ef2554d7189c411f\
+         5869ca52c5b83fa36ff216b9c1d30062bebcfd2dc5bce0911934fda79a86f6e6\
+         98ced759c3ff9b6477338f3da4f9cd8514ea9982ccafb341b2384dd902f3d1ab\
+         7ac61dd29c6f21ba5b862f3730e37cfdc4fd806c22f221"),
+    );
+    check_keystream(
+        "A.2 #3",
+        KEY_JABBERWOCKY,
+        NONCE_2,
+        42,
+        JABBERWOCKY,
+        &hex("62e6347f95ed87a45ffae7426f27a1df5fb69110044c0d73118effa95b01e5cf\
+         166d3df2d721caf9b21e5fb14c616871fd84c54f9d65b283196c7fe4f60553eb\
+         f39c6402c42234e32a356b3e764312a61a5532055716ead6962568f87d3f3f77\
+         04c6a8d1bcd1bf4d50d6154b6da731b187b58dfd728afa36757a797ac188d1"),
+    );
+}
+
+/// A.3: Poly1305 message authentication code, test vectors #1 to #11, computed in one
+/// update and one byte at a time.
+#[test]
+fn a3_poly1305() {
+    let r_s_10 = "0100000000000000040000000000000000000000000000000000000000000000";
+    let msg_10 = "e33594d7505e43b900000000000000003394d7505e4379cd0100000000000000\
+                  00000000000000000000000000000000";
+    let vectors: [(&str, Vec<u8>, &str); 11] = [
+        (KEY_0, vec![0; 64], "00000000000000000000000000000000"),
+        (
+            "0000000000000000000000000000000036e5f6b5c5e06070f0efca96227a863e",
+            IETF_CONTRIBUTION.to_vec(),
+            "36e5f6b5c5e06070f0efca96227a863e",
+        ),
+        (
+            "36e5f6b5c5e06070f0efca96227a863e00000000000000000000000000000000",
+            IETF_CONTRIBUTION.to_vec(),
+            "f3477e7cd95417af89a6b8794c310cf0",
+        ),
+        (KEY_JABBERWOCKY, JABBERWOCKY.to_vec(), "4541669a7eaaee61e708dc7cbcc5eb62"),
+        (
+            "0200000000000000000000000000000000000000000000000000000000000000",
+            hex("ffffffffffffffffffffffffffffffff"),
+            "03000000000000000000000000000000",
+        ),
+        (
+            "02000000000000000000000000000000ffffffffffffffffffffffffffffffff",
+            hex("02000000000000000000000000000000"),
+            "03000000000000000000000000000000",
+        ),
+        (
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            hex("fffffffffffffffffffffffffffffffff0ffffffffffffffffffffffffffffff\
+                 11000000000000000000000000000000"),
+            "05000000000000000000000000000000",
+        ),
+        (
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            hex("fffffffffffffffffffffffffffffffffbfefefefefefefefefefefefefefefe\
+                 01010101010101010101010101010101"),
+            "00000000000000000000000000000000",
+        ),
+        (
+            "0200000000000000000000000000000000000000000000000000000000000000",
+            hex("fdffffffffffffffffffffffffffffff"),
+            "faffffffffffffffffffffffffffffff",
+        ),
+        (
+            r_s_10,
+            [hex(msg_10), hex("01000000000000000000000000000000")].concat(),
+            "14000000000000005500000000000000",
+        ),
+        (r_s_10, hex(msg_10), "13000000000000000000000000000000"),
+    ];
+
+    for (i, (key, msg, tag)) in vectors.iter().enumerate() {
+        let key: [u8; 32] = hex(key).try_into().unwrap();
+
+        let mut mac = Poly1305::new(&key);
+        mac.update(msg);
+        assert_eq!(mac.finalize().to_vec(), hex(tag), "A.3 #{}", i + 1);
+
+        let mut mac = Poly1305::new(&key);
+        for byte in msg {
+            mac.update(core::slice::from_ref(byte));
+        }
+        assert_eq!(mac.finalize().to_vec(), hex(tag), "A.3 #{}, byte at a time", i + 1);
+    }
+}
+
+/// A.4: Poly1305 key generation using ChaCha20, test vectors #1 to #3.
+#[test]
+fn a4_poly1305_key_generation() {
+    let vectors = [
+        (KEY_0, NONCE_0, "76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7"),
+        (KEY_1, NONCE_2, "ecfa254f845f647473d3cb140da9e87606cb33066c447b87bc2666dde3fbb739"),
+        (
+            KEY_JABBERWOCKY,
+            NONCE_2,
+            "965e3bc6f9ec7ed9560808f4d229f94b137ff275ca9b3fcbdd59deaad23310ae",
+        ),
+    ];
+    for (i, (key, nonce, poly_key)) in vectors.into_iter().enumerate() {
+        let vector = format!("A.4 #{}", i + 1);
+        check_keystream(&vector, key, nonce, 0, &[0u8; 32], &hex(poly_key));
+    }
+}
+
+/// A.5: ChaCha20-Poly1305 AEAD decryption, through the public AEAD and through every backend.
+#[test]
+fn a5_aead_decryption() {
+    let key: [u8; 32] = hex(KEY_JABBERWOCKY).try_into().unwrap();
+    let nonce: [u8; 12] = hex("000000000102030405060708").try_into().unwrap();
+    let aad = hex("f33388860000000000004e91");
+    let ciphertext = hex("64a0861575861af460f062c79be643bd5e805cfd345cf389f108670ac76c8cb2\
+         4c6cfc18755d43eea09ee94e382d26b0bdb7b73c321b0100d4f03b7f355894cf\
+         332f830e710b97ce98c8a84abd0b948114ad176e008d33bd60f982b1ff37c855\
+         9797a06ef4f0ef61c186324e2b3506383606907b6a7c02b0f9f6157b53c867e4\
+         b9166c767b804d46a59b5216cde7a4e99040c5a40433225ee282a1b0a06c523e\
+         af4534d7f83fa1155b0047718cbc546a0d072b04b3564eea1b422273f548271a\
+         0bb2316053fa76991955ebd63159434ecebb4e466dae5a1073a6727627097a10\
+         49e617d91d361094fa68f0ff77987130305beaba2eda04df997b714d6c6f2c29\
+         a6ad5cb4022b02709b");
+    let tag: [u8; 16] = hex("eead9d67890cbb22392336fea1851f38").try_into().unwrap();
+    let plaintext: &[u8] = b"Internet-Drafts are draft documents valid for a maximum of six \
+months and may be updated, replaced, or obsoleted by other documents at any time. It is \
+inappropriate to use Internet-Drafts as reference material or to cite them other than as \
+/\xe2\x80\x9cwork in progress./\xe2\x80\x9d";
+
+    let aead = ChaCha20Poly1305::new(&key);
+    let mut buf = ciphertext.clone();
+    aead.decrypt_in_place_detached(&nonce, &aad, &mut buf, &tag).unwrap();
+    assert_eq!(buf, plaintext);
+    assert_eq!(aead.encrypt_in_place_detached(&nonce, &aad, &mut buf).unwrap(), tag);
+    assert_eq!(buf, ciphertext);
+
+    for (name, backend) in BACKENDS {
+        let mut buf = ciphertext.clone();
+        assert!(
+            backends::open(*backend, &key, &nonce, &aad, &mut buf, &tag),
+            "A.5: {name} backend"
+        );
+        assert_eq!(buf, plaintext, "A.5: {name} backend");
+        assert_eq!(
+            backends::seal(*backend, &key, &nonce, &aad, &mut buf),
+            tag,
+            "A.5: {name} backend"
+        );
+        assert_eq!(buf, ciphertext, "A.5: {name} backend");
+    }
+}
@@ -0,0 +1,93 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Sector encryption tests
+ *
+ * Checks the nonce layout against a plain `FastChaCha20`, and that single-sector,
+ * multi-sector and parallel encryption agree.
+ */
+
+use fast_chacha::sector::{SectorCipher, SECTOR_4096, SECTOR_512};
+use fast_chacha::{Error, FastChaCha20};
+use rand::RngCore;
+
+const KEY: [u8; 32] = [0x5a; 32];
+const VOLUME: [u8; 4] = [1, 2, 3, 4];
+
+/// A sector is ChaCha20 with nonce `volume_id || le64(sector_no)` from counter 0.
+#[test]
+fn sector_nonce_layout() {
+    for (sector_size, sector_no) in [(SECTOR_512, 0u64), (SECTOR_512, 7), (SECTOR_4096, 1 << 40)] {
+        let cipher = SectorCipher::new(&KEY, &VOLUME, sector_size).unwrap();
+        let mut sector = vec![0u8; sector_size];
+        cipher.encrypt_sector(sector_no, &mut sector).unwrap();
+
+        let mut nonce = [0u8; 12];
+        nonce[..4].copy_from_slice(&VOLUME);
+        nonce[4..].copy_from_slice(&sector_no.to_le_bytes());
+        let mut expected = vec![0u8; sector_size];
+        FastChaCha20::new(&KEY, &nonce).apply_keystream(&mut expected);
+        assert!(sector == expected, "sector {} of {} bytes", sector_no, sector_size);
+
+        let mut partial = vec![0u8; 100];
+        let mut positioned = cipher.sector_cipher(sector_no);
+        positioned.seek(300);
+        positioned.apply_keystream(&mut partial);
+        assert_eq!(&partial[..], &expected[300..400]);
+    }
+}
+
+/// Multi-sector runs equal per-sector calls and round-trip.
+#[test]
+fn multi_sector_roundtrip() {
+    let cipher = SectorCipher::new(&KEY, &VOLUME, SECTOR_512).unwrap();
+    let mut plain = vec![0u8; 16 * SECTOR_512];
+    rand::rng().fill_bytes(&mut plain);
+
+    let mut expected = plain.clone();
+    for (i, sector) in expected.chunks_mut(SECTOR_512).enumerate() {
+        cipher.encrypt_sector(1000 + i as u64, sector).unwrap();
+    }
+
+    let mut data = plain.clone();
+    cipher.encrypt_sectors(1000, &mut data).unwrap();
+    assert!(data == expected);
+    cipher.decrypt_sectors(1000, &mut data).unwrap();
+    assert!(data == plain);
+
+    assert_eq!(cipher.encrypt_sector(0, &mut [0u8; 511]), Err(Error::InvalidLength));
+    assert_eq!(cipher.encrypt_sectors(0, &mut [0u8; 513]), Err(Error::InvalidLength));
+    assert_eq!(cipher.encrypt_sectors(u64::MAX, &mut [0u8; 1024]), Err(Error::InvalidLength));
+    assert!(SectorCipher::new(&KEY, &VOLUME, 520).is_err());
+}
+
+/// The rayon variant matches the serial one.
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_matches_serial() {
+    let cipher = SectorCipher::new(&KEY, &VOLUME, SECTOR_4096).unwrap();
+    let mut plain = vec![0u8; 300 * SECTOR_4096];
+    rand::rng().fill_bytes(&mut plain);
+
+    let mut expected = plain.clone();
+    cipher.encrypt_sectors(5, &mut expected).unwrap();
+    let mut data = plain.clone();
+    cipher.encrypt_sectors_parallel(5, &mut data).unwrap();
+    assert!(data == expected);
+    cipher.decrypt_sectors_parallel(5, &mut data).unwrap();
+    assert!(data == plain);
+}
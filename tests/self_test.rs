@@ -0,0 +1,39 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Power-on Self-Test
+ *
+ * With the `self_test` feature, the first cipher created runs the known-answer tests and
+ * the outcome is cached and reported through `fast_chacha::self_test`.
+ */
+
+#![cfg(feature = "self_test")]
+
+use fast_chacha::self_test::{self, Status};
+use fast_chacha::FastChaCha20;
+
+/// The only test in this binary, so nothing has created a cipher before it.
+#[test]
+fn runs_on_first_use_and_caches_the_result() {
+    assert_eq!(self_test::status(), Status::NotRun);
+
+    let mut data = [0u8; 64];
+    FastChaCha20::new(&[0u8; 32], &[0u8; 12]).apply_keystream(&mut data);
+    assert_eq!(self_test::status(), Status::Passed);
+
+    assert_eq!(self_test::run(), Status::Passed);
+}
@@ -0,0 +1,101 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # chacha20-poly1305@openssh.com Test Vectors
+ *
+ * The expected packets were computed from OpenSSH's PROTOCOL.chacha20poly1305 with
+ * OpenSSL's ChaCha20 (64-bit counter layout) and Poly1305, using the key 00..3f.
+ * They cover sequence number 0, a multi-block payload and the largest sequence number.
+ */
+
+use fast_chacha::ssh::{SshChaCha20Poly1305, LENGTH_BYTES, TAG_BYTES};
+use fast_chacha::Error;
+
+/// Decodes a hex string into bytes.
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+/// (sequence number, plaintext packet without tag, encrypted packet with tag)
+fn vectors() -> Vec<(u32, Vec<u8>, Vec<u8>)> {
+    let long_payload: Vec<u8> = (0u8..200).collect();
+    let mut long = hex("000000c8");
+    long.extend_from_slice(&long_payload);
+
+    vec![
+        (
+            0,
+            hex("0000001c0b050000000c7373682d757365726175746800000000000000000000"),
+            hex("94450e4513bd4231adead5a27b4c2912ca312f528cd9f3f5e1ad5b5cecf8fc12\
+                 e39156b4a965c2e5702853068bb3a7ba"),
+        ),
+        (
+            7,
+            long,
+            hex("a39afc62284717404a862c596464b1fbdb82dd2324a322256af62b94587301c4\
+                 9c4e1d4b8476d3863147d29e84fb4c053f1f2558479da2ecab167ec442dd1c81\
+                 ef9369ce4583a779650712a16ca1c5a82a08b3bf389c41efa960a2a59ecd67a0\
+                 3a662f69c0f4323b312c43502233cb70b7239ad8123cb2b6597b21a6fe113f22\
+                 04b9f57e9db9f3424824342aac8292127a694252b0d666ede693b2a71c2b20ed\
+                 83ea2a5b79f69045fd6c9b81c1c0c614d5f7b69b679f7b5689df25ea5a158932\
+                 f8b0fab6baa5269870476def01c96b9c9de02c9d2a900f413f5503ca"),
+        ),
+        (
+            u32::MAX,
+            hex("0000000401020304"),
+            hex("b90ee4b408900f9f22aa89f0d457cc00ea0c4818b46a0c3f"),
+        ),
+    ]
+}
+
+fn cipher() -> SshChaCha20Poly1305 {
+    SshChaCha20Poly1305::new(&core::array::from_fn(|i| i as u8))
+}
+
+/// Encryption must reproduce the reference packets.
+#[test]
+fn encrypt_packet_vectors() {
+    let cipher = cipher();
+    for (seq, plain, expected) in vectors() {
+        let mut packet = plain.clone();
+        packet.extend_from_slice(&[0u8; TAG_BYTES]);
+        cipher.encrypt_packet(seq, &mut packet).unwrap();
+        assert_eq!(packet, expected, "packet mismatch for seqnr {}", seq);
+    }
+}
+
+/// Length and packet decryption must recover the plaintext and reject tampering.
+#[test]
+fn decrypt_packet_vectors() {
+    let cipher = cipher();
+    for (seq, plain, encrypted) in vectors() {
+        let len = cipher.decrypt_length(seq, encrypted[..LENGTH_BYTES].try_into().unwrap());
+        assert_eq!(len as usize, plain.len() - LENGTH_BYTES);
+
+        let mut packet = encrypted.clone();
+        cipher.decrypt_packet(seq, &mut packet).unwrap();
+        assert_eq!(&packet[..plain.len()], &plain[..]);
+
+        let mut forged = encrypted.clone();
+        forged[0] ^= 0x80;
+        assert_eq!(cipher.decrypt_packet(seq, &mut forged), Err(Error::AuthenticationFailed));
+        assert_eq!(
+            cipher.decrypt_packet(seq.wrapping_add(1), &mut encrypted.clone()),
+            Err(Error::AuthenticationFailed)
+        );
+    }
+}
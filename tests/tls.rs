@@ -0,0 +1,251 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # TLS 1.3 Record Protection Tests
+ *
+ * The expected records were computed per RFC 8446 §5.2–5.3 with OpenSSL's
+ * ChaCha20-Poly1305 (via Python `cryptography`), with key 00..1f and IV
+ * 5b78923dee08579033e523d9, at sequence numbers 0, 1 and 0x0123456789abcdef.
+ *
+ * The DTLS 1.3 record was computed the same way per RFC 9147 §4, with sn_key 20..3f, epoch
+ * 3 and sequence number 0x0102, its record number mask coming from OpenSSL's ChaCha20.
+ */
+
+use fast_chacha::aead::ChaCha20Poly1305;
+use fast_chacha::quic::quic_header_mask;
+use fast_chacha::tls::{
+    dtls_record_len, DtlsRecordProtection, RecordProtection, ALERT, APPLICATION_DATA,
+    DTLS_HEADER_BYTES, HANDSHAKE, HEADER_BYTES, TAG_BYTES,
+};
+use fast_chacha::Error;
+
+/// Decodes a hex string into bytes.
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+const KEY: [u8; 32] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+    0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+];
+const IV: [u8; 12] = [0x5b, 0x78, 0x92, 0x3d, 0xee, 0x08, 0x57, 0x90, 0x33, 0xe5, 0x23, 0xd9];
+
+/// Record at sequence number 0: a handshake `Finished` message, no padding.
+fn finished() -> (Vec<u8>, Vec<u8>) {
+    let mut content = hex("14000020");
+    content.extend(0u8..32);
+    let record = hex("1703030035d73179f03c25722ef4920685d95fcd11e69d8ad382810f3b72c724\
+         1ba0e33adebaa5e40e62bab9d407501eb4fafea88411fad50bc4");
+    (content, record)
+}
+
+/// Record at sequence number 1: application data with 13 bytes of padding.
+const REQUEST: &[u8] = b"GET / HTTP/1.1\r\n\r\n";
+const REQUEST_RECORD: &str = "17030300305eabd143c7c20edb35b4e8f596ba12e4e7ae848911eeda717416c0\
+                              e9a1e14c906274af7f2cafe0f0dfcdec224394991a";
+
+/// Sealing must reproduce the reference records, including the per-record nonce.
+#[test]
+fn seal_record_vectors() {
+    let mut tx = RecordProtection::new(&KEY, &IV);
+    let mut out = [0u8; 256];
+
+    let (content, expected) = finished();
+    let n = tx.seal_record(HANDSHAKE, &content, 0, &mut out).unwrap();
+    assert_eq!(&out[..n], &expected[..]);
+
+    let n = tx.seal_record(APPLICATION_DATA, REQUEST, 13, &mut out).unwrap();
+    assert_eq!(&out[..n], &hex(REQUEST_RECORD)[..]);
+    assert_eq!(tx.sequence_number(), 2);
+}
+
+/// Opening must strip padding, return the real content type, and reject tampering,
+/// records out of order and all-padding plaintexts.
+#[test]
+fn open_record_vectors() {
+    let mut rx = RecordProtection::new(&KEY, &IV);
+
+    let (content, mut record) = finished();
+    let (content_type, plain) = rx.open_record(&mut record).unwrap();
+    assert_eq!((content_type, plain), (HANDSHAKE, &content[..]));
+
+    let mut record = hex(REQUEST_RECORD);
+    record[HEADER_BYTES] ^= 1;
+    assert_eq!(rx.open_record(&mut record), Err(Error::AuthenticationFailed));
+
+    // The failed record consumed sequence number 1, so the genuine one no longer opens.
+    let mut record = hex(REQUEST_RECORD);
+    assert_eq!(rx.open_record(&mut record), Err(Error::AuthenticationFailed));
+
+    let mut rx = RecordProtection::new(&KEY, &IV);
+    let mut tx = RecordProtection::new(&KEY, &IV);
+    let mut out = [0u8; HEADER_BYTES + 8 + TAG_BYTES];
+    let n = tx.seal_record(0, &[], 7, &mut out).unwrap();
+    assert_eq!(rx.open_record(&mut out[..n]), Err(Error::InvalidPadding));
+}
+
+/// Sequence number 0x0123456789abcdef must be XORed into the IV, and the last sequence
+/// number must be refused.
+#[test]
+fn sequence_numbers() {
+    let mut tx = RecordProtection::new(&KEY, &IV);
+    let mut out = [0u8; 64];
+
+    tx.set_sequence_number(0x0123_4567_89ab_cdef);
+    let n = tx.seal_record(ALERT, &[1, 0], 0, &mut out).unwrap();
+    assert_eq!(&out[..n], &hex("1703030013aac42c3aba9fe7601481dbadf9075bc820533d")[..]);
+
+    tx.set_sequence_number(u64::MAX - 1);
+    tx.seal_record(ALERT, &[1, 0], 0, &mut out).unwrap();
+    assert_eq!(tx.seal_record(ALERT, &[1, 0], 0, &mut out), Err(Error::NonceExhausted));
+    assert_eq!(tx.sequence_number(), u64::MAX);
+}
+
+/// Round trips of various sizes, and length limits.
+#[test]
+fn roundtrip_and_lengths() {
+    let mut tx = RecordProtection::new(&KEY, &IV);
+    let mut rx = RecordProtection::new(&KEY, &IV);
+    let mut out = vec![0u8; HEADER_BYTES + (1 << 14) + 1 + TAG_BYTES];
+
+    for len in [0usize, 1, 63, 64, 1000, 1 << 14] {
+        let content: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        let n = tx.seal_record(ALERT, &content, 0, &mut out).unwrap();
+        let (content_type, plain) = rx.open_record(&mut out[..n]).unwrap();
+        assert_eq!((content_type, plain), (ALERT, &content[..]));
+    }
+
+    assert_eq!(tx.seal_record(ALERT, &[0u8; 1 << 14], 1, &mut out), Err(Error::InvalidLength));
+    assert_eq!(tx.seal_record(ALERT, &[0u8; 10], 0, &mut out[..20]), Err(Error::InvalidLength));
+    assert_eq!(tx.seal_record(ALERT, &[0u8; 10], usize::MAX, &mut out), Err(Error::InvalidLength));
+    assert_eq!(tx.seal_record(ALERT, &[], usize::MAX - 1, &mut out), Err(Error::InvalidLength));
+}
+
+const SN_KEY: [u8; 32] = [
+    0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x2b, 0x2c, 0x2d, 0x2e, 0x2f,
+    0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3a, 0x3b, 0x3c, 0x3d, 0x3e, 0x3f,
+];
+const DTLS_RECORD: &str = "2f60d3001864ded5aeb3e54d4a7ac03b4996faa665e71cbc1d2d853d1f";
+
+/// Builds a DTLS 1.3 record by hand with an 8-bit sequence number and no length field.
+fn short_dtls_record(epoch: u8, seq: u64, content: &[u8]) -> Vec<u8> {
+    let mut record = vec![0x20 | epoch, seq as u8];
+    let mut inner = [content, &[APPLICATION_DATA]].concat();
+    let mut nonce = IV;
+    for (n, s) in nonce[4..].iter_mut().zip(seq.to_be_bytes()) {
+        *n ^= s;
+    }
+    let tag = ChaCha20Poly1305::new(&KEY).encrypt_in_place_detached(&nonce, &record, &mut inner);
+    record.extend_from_slice(&inner);
+    record.extend_from_slice(&tag.unwrap());
+    record[1] ^= quic_header_mask(&SN_KEY, record[2..18].try_into().unwrap())[0];
+    record
+}
+
+/// Sealing must produce the reference DTLS record, and opening must return its content
+/// and full sequence number.
+#[test]
+fn dtls_record_vector() {
+    let mut tx = DtlsRecordProtection::new(&KEY, &IV, &SN_KEY, 3);
+    tx.set_sequence_number(0x0102);
+    let mut out = [0u8; 64];
+    let n = tx.seal_record(APPLICATION_DATA, b"ping", 3, &mut out).unwrap();
+    assert_eq!(&out[..n], &hex(DTLS_RECORD)[..]);
+    assert_eq!(n, DTLS_HEADER_BYTES + 4 + 1 + 3 + TAG_BYTES);
+    assert_eq!(tx.sequence_number(), 0x0103);
+
+    let mut rx = DtlsRecordProtection::new(&KEY, &IV, &SN_KEY, 3);
+    rx.set_sequence_number(0x0100);
+    let (content_type, content, seq) = rx.open_record(&mut out[..n]).unwrap();
+    assert_eq!((content_type, content, seq), (APPLICATION_DATA, &b"ping"[..], 0x0102));
+    assert_eq!(rx.sequence_number(), 0x0103);
+}
+
+/// Records may arrive out of order and with 8-bit sequence numbers; the full number is
+/// rebuilt around the highest record opened so far.
+#[test]
+fn dtls_sequence_number_reconstruction() {
+    let mut tx = DtlsRecordProtection::new(&KEY, &IV, &SN_KEY, 1);
+    let mut rx = DtlsRecordProtection::new(&KEY, &IV, &SN_KEY, 1);
+    let records: Vec<Vec<u8>> = (0..70_000u32)
+        .map(|i| {
+            let mut out = vec![0u8; DTLS_HEADER_BYTES + 4 + 1 + TAG_BYTES];
+            tx.seal_record(APPLICATION_DATA, &i.to_be_bytes(), 0, &mut out).unwrap();
+            out
+        })
+        .collect();
+
+    // In order across the 16-bit wrap, with some records lost and some swapped.
+    for i in (0..70_000usize).filter(|i| i % 7 != 3).map(|i| {
+        if i % 5 == 1 {
+            i + 1
+        } else if i % 5 == 2 {
+            i - 1
+        } else {
+            i
+        }
+    }) {
+        let mut record = records[i].clone();
+        let (_, content, seq) = rx.open_record(&mut record).unwrap();
+        assert_eq!((content, seq), (&(i as u32).to_be_bytes()[..], i as u64));
+    }
+
+    let mut rx = DtlsRecordProtection::new(&KEY, &IV, &SN_KEY, 1);
+    rx.set_sequence_number(0x1ff);
+    let mut record = short_dtls_record(1, 0x200, b"next");
+    assert_eq!(dtls_record_len(&record), Ok(record.len()));
+    assert_eq!(rx.open_record(&mut record).unwrap(), (APPLICATION_DATA, &b"next"[..], 0x200));
+    let mut record = short_dtls_record(1, 0x1f0, b"late");
+    assert_eq!(rx.open_record(&mut record).unwrap(), (APPLICATION_DATA, &b"late"[..], 0x1f0));
+    assert_eq!(rx.sequence_number(), 0x201);
+}
+
+/// Forged records are dropped without consuming their sequence number, and headers of
+/// other epochs, with connection IDs or with a wrong length are refused.
+#[test]
+fn dtls_rejections_and_datagrams() {
+    let mut tx = DtlsRecordProtection::new(&KEY, &IV, &SN_KEY, 2);
+    let mut rx = DtlsRecordProtection::new(&KEY, &IV, &SN_KEY, 2);
+    let mut datagram = vec![0u8; 2 * (DTLS_HEADER_BYTES + 3 + 1 + TAG_BYTES)];
+    let n = tx.seal_record(HANDSHAKE, b"one", 0, &mut datagram).unwrap();
+    tx.seal_record(HANDSHAKE, b"two", 0, &mut datagram[n..]).unwrap();
+    assert_eq!(dtls_record_len(&datagram), Ok(n));
+
+    let (first, second) = datagram.split_at_mut(n);
+    let genuine = first.to_vec();
+    first[DTLS_HEADER_BYTES] ^= 1;
+    let forged = first.to_vec();
+    assert_eq!(rx.open_record(first), Err(Error::AuthenticationFailed));
+    assert_eq!(&first[..], &forged[..]);
+    assert_eq!(rx.sequence_number(), 0);
+
+    first.copy_from_slice(&genuine);
+    assert_eq!(rx.open_record(first).unwrap(), (HANDSHAKE, &b"one"[..], 0));
+    assert_eq!(rx.open_record(&mut second[..n - 1]), Err(Error::InvalidLength));
+    assert_eq!(rx.open_record(second).unwrap(), (HANDSHAKE, &b"two"[..], 1));
+
+    let mut other_epoch = DtlsRecordProtection::new(&KEY, &IV, &SN_KEY, 3);
+    assert_eq!(other_epoch.open_record(&mut genuine.clone()), Err(Error::InvalidHeader));
+    let mut with_cid = genuine.clone();
+    with_cid[0] |= 0x10;
+    assert_eq!(rx.open_record(&mut with_cid), Err(Error::InvalidHeader));
+    assert_eq!(dtls_record_len(&[0x17, 3, 3, 0, 0]), Err(Error::InvalidHeader));
+
+    tx.set_sequence_number(u64::MAX);
+    assert_eq!(tx.seal_record(ALERT, &[1, 0], 0, &mut datagram), Err(Error::NonceExhausted));
+    assert_eq!(tx.seal_record(ALERT, &[], usize::MAX, &mut datagram), Err(Error::InvalidLength));
+}
@@ -0,0 +1,121 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # WebAssembly tests
+ *
+ * Run under Node with wasm-bindgen-test:
+ * `cargo test --target wasm32-unknown-unknown --features wasm --test wasm`.
+ *
+ * Compares the keystream (simd128 backend when enabled) with the scalar block function,
+ * including across a block counter wrap, and checks the JavaScript-facing classes against
+ * RFC 8439 §2.8.2.
+ */
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use fast_chacha::fallback_chacha20;
+use fast_chacha::wasm::{WasmChaCha20, WasmChaCha20Poly1305, WasmXChaCha20Poly1305};
+use fast_chacha::FastChaCha20;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Decodes a hex string into bytes.
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+const KEY: [u8; 32] = [0x42; 32];
+const NONCE: [u8; 12] = [0, 0, 0, 9, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+
+/// Keystream computed one block at a time with the scalar block function.
+fn scalar_keystream(len: usize, first_block: u32) -> Vec<u8> {
+    let key: [u32; 8] =
+        core::array::from_fn(|i| u32::from_le_bytes(KEY[i * 4..i * 4 + 4].try_into().unwrap()));
+    let mut counter = [first_block, 0, 0, 0];
+    for i in 0..3 {
+        counter[i + 1] = u32::from_le_bytes(NONCE[i * 4..i * 4 + 4].try_into().unwrap());
+    }
+    let mut out = vec![0u8; len];
+    for block in out.chunks_mut(64) {
+        fallback_chacha20::xor(block, false, &key, &counter, 10);
+        counter[0] = counter[0].wrapping_add(1);
+    }
+    out
+}
+
+#[wasm_bindgen_test]
+fn keystream_matches_scalar() {
+    for len in [1, 63, 64, 255, 256, 257, 1000, 4096] {
+        let mut data = vec![0u8; len];
+        FastChaCha20::new(&KEY, &NONCE).apply_keystream(&mut data);
+        assert_eq!(data, scalar_keystream(len, 0), "len {len}");
+    }
+
+    // Blocks 0xffff_fffe, 0xffff_ffff, 0, 1 in one four-block group.
+    let mut cipher = FastChaCha20::new(&KEY, &NONCE);
+    cipher.set_counter(0xffff_fffe);
+    let mut data = vec![0u8; 512];
+    cipher.apply_keystream(&mut data);
+    assert_eq!(data, scalar_keystream(512, 0xffff_fffe));
+}
+
+#[wasm_bindgen_test]
+fn stream_cipher_class() {
+    let mut whole = vec![7u8; 700];
+    WasmChaCha20::new(&KEY, &NONCE).unwrap().apply(&mut whole);
+
+    let mut cipher = WasmChaCha20::new(&KEY, &NONCE).unwrap();
+    let mut split = vec![7u8; 700];
+    let (head, tail) = split.split_at_mut(300);
+    cipher.apply(head);
+    cipher.apply(tail);
+    assert_eq!(cipher.position(), 700);
+    assert_eq!(split, whole);
+
+    cipher.seek(100);
+    let mut part = vec![7u8; 50];
+    cipher.apply(&mut part);
+    assert_eq!(part, &whole[100..150]);
+
+    assert!(WasmChaCha20::new(&KEY[..31], &NONCE).is_err());
+}
+
+#[wasm_bindgen_test]
+fn aead_classes() {
+    let key: Vec<u8> = (0x80..0xa0).collect();
+    let nonce = hex("070000004041424344454647");
+    let aad = hex("50515253c0c1c2c3c4c5c6c7");
+    let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only \
+one tip for the future, sunscreen would be it.";
+
+    let aead = WasmChaCha20Poly1305::new(&key).unwrap();
+    let sealed = aead.encrypt(&nonce, plaintext, &aad).unwrap();
+    assert_eq!(sealed.len(), plaintext.len() + 16);
+    assert_eq!(&sealed[..16], &hex("d31a8d34648e60db7b86afbc53ef7ec2")[..]);
+    assert_eq!(&sealed[plaintext.len()..], &hex("1ae10b594f09e26a7e902ecbd0600691")[..]);
+    assert_eq!(aead.decrypt(&nonce, &sealed, &aad).unwrap(), plaintext);
+
+    let mut tampered = sealed.clone();
+    tampered[0] ^= 1;
+    assert!(aead.decrypt(&nonce, &tampered, &aad).is_err());
+    assert!(aead.decrypt(&nonce, &sealed[..15], &aad).is_err());
+
+    let xaead = WasmXChaCha20Poly1305::new(&key).unwrap();
+    let xnonce = [3u8; 24];
+    let sealed = xaead.encrypt(&xnonce, plaintext, &[]).unwrap();
+    assert_eq!(xaead.decrypt(&xnonce, &sealed, &[]).unwrap(), plaintext);
+    assert!(xaead.encrypt(&nonce, plaintext, &[]).is_err());
+}
@@ -0,0 +1,123 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # Wycheproof ChaCha20-Poly1305 and XChaCha20-Poly1305 Suites
+ *
+ * Runs Google's Project Wycheproof `chacha20_poly1305_test.json` and
+ * `xchacha20_poly1305_test.json` (bundled by the `wycheproof` crate) against the public
+ * AEAD types and against the same construction on every backend in `backends/mod.rs`.
+ *
+ * Valid cases must encrypt to the expected ciphertext and tag and decrypt back; invalid
+ * cases must be rejected without touching the buffer. Groups with a nonce length other
+ * than 96 (resp. 192) bits are skipped: the typed `&[u8; N]` nonce parameters cannot
+ * express them.
+ */
+
+mod backends;
+
+use backends::BACKENDS;
+use fast_chacha::aead::{ChaCha20Poly1305, XChaCha20Poly1305};
+use fast_chacha::Error;
+use wycheproof::aead::{Test, TestName, TestSet};
+
+/// Runs one case against every backend with an already derived (key, 96-bit nonce).
+fn check_backends(suite: &str, tc: &Test, key: &[u8; 32], nonce: &[u8; 12]) {
+    let tag: [u8; 16] = tc.tag[..].try_into().unwrap();
+    for (name, backend) in BACKENDS {
+        let mut buf = tc.ct.to_vec();
+        let opened = backends::open(*backend, key, nonce, &tc.aad, &mut buf, &tag);
+        if tc.result.must_fail() {
+            assert!(!opened, "{suite} #{}: {name} backend accepted a forgery", tc.tc_id);
+            assert_eq!(buf[..], tc.ct[..], "{suite} #{}: {name} backend", tc.tc_id);
+            continue;
+        }
+        assert!(opened, "{suite} #{} ({}): {name} backend", tc.tc_id, tc.comment);
+        assert_eq!(buf[..], tc.pt[..], "{suite} #{}: {name} backend", tc.tc_id);
+
+        let sealed = backends::seal(*backend, key, nonce, &tc.aad, &mut buf);
+        assert_eq!(buf[..], tc.ct[..], "{suite} #{}: {name} backend", tc.tc_id);
+        assert_eq!(sealed, tag, "{suite} #{}: {name} backend", tc.tc_id);
+    }
+}
+
+/// Runs a suite, calling `check` for every case with a `nonce_bits` nonce, and returns
+/// the number of cases run.
+fn run_suite(name: TestName, nonce_bits: usize, mut check: impl FnMut(&Test)) -> usize {
+    let set = TestSet::load(name).unwrap();
+    let mut count = 0;
+    for group in set.test_groups.iter().filter(|g| g.nonce_size == nonce_bits) {
+        assert_eq!((group.key_size, group.tag_size), (256, 128));
+        for tc in &group.tests {
+            check(tc);
+            count += 1;
+        }
+    }
+    count
+}
+
+#[test]
+fn wycheproof_chacha20_poly1305() {
+    let suite = "chacha20_poly1305";
+    let count = run_suite(TestName::ChaCha20Poly1305, 96, |tc| {
+        let key: [u8; 32] = tc.key[..].try_into().unwrap();
+        let nonce: [u8; 12] = tc.nonce[..].try_into().unwrap();
+        let tag: [u8; 16] = tc.tag[..].try_into().unwrap();
+        let aead = ChaCha20Poly1305::new(&key);
+
+        let mut buf = tc.ct.to_vec();
+        let opened = aead.decrypt_in_place_detached(&nonce, &tc.aad, &mut buf, &tag);
+        if tc.result.must_fail() {
+            assert_eq!(opened, Err(Error::AuthenticationFailed), "{suite} #{}", tc.tc_id);
+            assert_eq!(buf[..], tc.ct[..], "{suite} #{}", tc.tc_id);
+        } else {
+            assert_eq!(opened, Ok(()), "{suite} #{} ({})", tc.tc_id, tc.comment);
+            assert_eq!(buf[..], tc.pt[..], "{suite} #{}", tc.tc_id);
+            let sealed = aead.encrypt_in_place_detached(&nonce, &tc.aad, &mut buf).unwrap();
+            assert_eq!((&buf[..], sealed), (&tc.ct[..], tag), "{suite} #{}", tc.tc_id);
+        }
+
+        check_backends(suite, tc, &key, &nonce);
+    });
+    assert!(count > 250, "only {count} {suite} cases ran");
+}
+
+#[test]
+fn wycheproof_xchacha20_poly1305() {
+    let suite = "xchacha20_poly1305";
+    let count = run_suite(TestName::XChaCha20Poly1305, 192, |tc| {
+        let key: [u8; 32] = tc.key[..].try_into().unwrap();
+        let nonce: [u8; 24] = tc.nonce[..].try_into().unwrap();
+        let tag: [u8; 16] = tc.tag[..].try_into().unwrap();
+        let aead = XChaCha20Poly1305::new(&key);
+
+        let mut buf = tc.ct.to_vec();
+        let opened = aead.decrypt_in_place_detached(&nonce, &tc.aad, &mut buf, &tag);
+        if tc.result.must_fail() {
+            assert_eq!(opened, Err(Error::AuthenticationFailed), "{suite} #{}", tc.tc_id);
+            assert_eq!(buf[..], tc.ct[..], "{suite} #{}", tc.tc_id);
+        } else {
+            assert_eq!(opened, Ok(()), "{suite} #{} ({})", tc.tc_id, tc.comment);
+            assert_eq!(buf[..], tc.pt[..], "{suite} #{}", tc.tc_id);
+            let sealed = aead.encrypt_in_place_detached(&nonce, &tc.aad, &mut buf).unwrap();
+            assert_eq!((&buf[..], sealed), (&tc.ct[..], tag), "{suite} #{}", tc.tc_id);
+        }
+
+        let (subkey, chacha_nonce) = backends::xchacha_subkey(&key, &nonce);
+        check_backends(suite, tc, &subkey, &chacha_nonce);
+    });
+    assert!(count > 250, "only {count} {suite} cases ran");
+}
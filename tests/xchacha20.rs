@@ -0,0 +1,66 @@
+/*
+ * Copyright 2025 sh0rch <sh0rch@iwl.dev>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*!
+ * # HChaCha20 / XChaCha20 Test Vectors
+ *
+ * HChaCha20 is checked against draft-irtf-cfrg-xchacha §2.2.1, and the XChaCha20
+ * keystream against libsodium's `crypto_stream_xchacha20`.
+ */
+
+use fast_chacha::fallback_chacha20::hchacha;
+use fast_chacha::FastChaCha20;
+
+/// Decodes a hex string into bytes.
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+/// Reads little-endian u32 words from bytes.
+fn words<const N: usize>(b: &[u8]) -> [u32; N] {
+    core::array::from_fn(|i| u32::from_le_bytes(b[i * 4..i * 4 + 4].try_into().unwrap()))
+}
+
+/// HChaCha20 test vector from draft-irtf-cfrg-xchacha §2.2.1.
+#[test]
+fn hchacha20_draft_vector() {
+    let key: Vec<u8> = (0u8..32).collect();
+    let input = hex("000000090000004a0000000031415927");
+    let expected = hex("82413b4227b27bfed30e42508a877d73a0f9e4d58a74a853c12ec41326d3ecdc");
+
+    let out = hchacha(&words(&key), &words(&input), 10);
+    let out: Vec<u8> = out.iter().flat_map(|w| w.to_le_bytes()).collect();
+    assert_eq!(out, expected, "HChaCha20 output mismatch");
+}
+
+/// XChaCha20 keystream, cross-checked with libsodium, on the asm and fallback paths.
+#[test]
+fn xchacha20_keystream() {
+    let key: [u8; 32] = core::array::from_fn(|i| 0x80 + i as u8);
+    let nonce: [u8; 24] = core::array::from_fn(|i| 0x40 + i as u8);
+    let expected = hex("7b191f80f361f099094f6f4b8fb97df847cc6873a8f2b190dd73807183f907d5\
+         a1cb27385b00329f7ddc127059d6882551a120e7631352e9b0381572e950155a\
+         f10c73f45bf0f45afb1277d3f6ae9d553247726e05449ceccabaf50c42550dc8\
+         003c107d");
+
+    let mut fast = vec![0u8; 100];
+    FastChaCha20::new_xchacha20(&key, &nonce).apply_keystream(&mut fast);
+    assert_eq!(fast, expected, "XChaCha20 keystream mismatch");
+
+    let mut pure = vec![0u8; 100];
+    FastChaCha20::new_xchacha20(&key, &nonce).apply_keystream_pure(&mut pure, 10);
+    assert_eq!(pure, expected, "XChaCha20 (Fallback) keystream mismatch");
+}